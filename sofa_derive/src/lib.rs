@@ -0,0 +1,154 @@
+//! # Sofa derive
+//!
+//! Derive macros for [Sofa](https://docs.rs/sofa), enabled through its
+//! `derive` feature.
+//!
+//! `#[derive(CouchDocument)]` implements `sofa::CouchDocument` for a struct
+//! with named fields, so that it can be given to `Database::get_typed`,
+//! `save_typed` and `remove_typed` as is. The struct must also implement
+//! serde's `Serialize` and `Deserialize`, which keep mapping its fields.
+//!
+//! The document ID and revision are read from the fields marked
+//! `#[couch(id)]` and `#[couch(rev)]`, or else from the fields named `_id`
+//! and `_rev`. The ID must be a `String` or a `DocumentId`, the revision a
+//! `String` or an `Option<String>`.
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize, CouchDocument)]
+//! struct Sofa {
+//!     #[couch(id)]
+//!     #[serde(rename = "_id")]
+//!     id: String,
+//!     #[couch(rev)]
+//!     #[serde(rename = "_rev", skip_serializing_if = "Option::is_none")]
+//!     rev: Option<String>,
+//!     seats: u32,
+//! }
+//! ```
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{Data, DeriveInput, Error, Field, Fields, Meta, NestedMeta, Type};
+
+#[proc_macro_derive(CouchDocument, attributes(couch))]
+pub fn derive_couch_document(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => fields.named.iter().collect::<Vec<&Field>>(),
+            _ => return Err(Error::new_spanned(input, "CouchDocument requires a struct with named fields")),
+        },
+        _ => return Err(Error::new_spanned(input, "CouchDocument can only be derived for structs")),
+    };
+
+    let id = find_field(input, &fields, "id")?;
+    let rev = find_field(input, &fields, "rev")?;
+    let id_ident = &id.ident;
+    let rev_ident = &rev.ident;
+
+    let (rev_getter, rev_setter) = if is_option(&rev.ty) {
+        (
+            quote!(self.#rev_ident.as_deref()),
+            quote!(self.#rev_ident = Some(rev)),
+        )
+    } else {
+        (
+            quote!(if self.#rev_ident.is_empty() { None } else { Some(&self.#rev_ident) }),
+            quote!(self.#rev_ident = rev),
+        )
+    };
+
+    // The fields of generic structs only implement serde's traits when their
+    // type parameters do, as bounded by serde's own derives
+    let mut generics = input.generics.clone();
+    generics.make_where_clause().predicates.push(syn::parse_quote! {
+        Self: ::sofa::__serde::Serialize + ::sofa::__serde::DeserializeOwned
+    });
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::sofa::CouchDocument for #name #ty_generics #where_clause {
+            fn id(&self) -> &str {
+                &self.#id_ident
+            }
+
+            fn rev(&self) -> Option<&str> {
+                #rev_getter
+            }
+
+            fn set_id(&mut self, id: String) {
+                self.#id_ident = id.into();
+            }
+
+            fn set_rev(&mut self, rev: String) {
+                #rev_setter;
+            }
+        }
+    })
+}
+
+/// Finds the field marked `#[couch(<role>)]`, or else the one named
+/// `_<role>`
+fn find_field<'a>(input: &DeriveInput, fields: &[&'a Field], role: &str) -> Result<&'a Field, Error> {
+    let mut marked = Vec::new();
+
+    for field in fields {
+        for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("couch")) {
+            if let Meta::List(list) = attr.parse_meta()? {
+                for nested in list.nested {
+                    match nested {
+                        NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("id") || path.is_ident("rev") => {
+                            if path.is_ident(role) {
+                                marked.push(*field);
+                            }
+                        }
+                        other => return Err(Error::new_spanned(other, "expected `id` or `rev`")),
+                    }
+                }
+            } else {
+                return Err(Error::new_spanned(attr, "expected `#[couch(id)]` or `#[couch(rev)]`"));
+            }
+        }
+    }
+
+    match marked.len() {
+        0 => {}
+        1 => return Ok(marked[0]),
+        _ => return Err(Error::new_spanned(marked[1], format!("more than one field is marked `#[couch({})]`", role))),
+    }
+
+    let fallback = format!("_{}", role);
+    fields
+        .iter()
+        .find(|field| field.ident.as_ref().is_some_and(|ident| *ident == fallback))
+        .cloned()
+        .ok_or_else(|| {
+            Error::new_spanned(
+                &input.ident,
+                format!("CouchDocument requires a field marked `#[couch({})]` or named `{}`", role, fallback),
+            )
+        })
+}
+
+fn is_option(ty: &Type) -> bool {
+    match *ty {
+        Type::Path(ref path) => path.path.segments.last().is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
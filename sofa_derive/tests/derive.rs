@@ -0,0 +1,68 @@
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+#[macro_use]
+extern crate serde_json;
+extern crate sofa;
+
+use sofa::CouchDocument;
+
+#[derive(Serialize, Deserialize, CouchDocument, Debug, PartialEq)]
+struct Named {
+    _id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    _rev: String,
+    seats: u32,
+}
+
+#[derive(Serialize, Deserialize, CouchDocument, Debug, PartialEq)]
+struct Marked<T> {
+    #[couch(id)]
+    #[serde(rename = "_id")]
+    key: String,
+    #[couch(rev)]
+    #[serde(rename = "_rev", skip_serializing_if = "Option::is_none")]
+    revision: Option<String>,
+    #[serde(flatten)]
+    data: T,
+}
+
+#[test]
+fn should_use_fields_named_id_and_rev() {
+    let mut doc = Named {
+        _id: "chesterfield".to_string(),
+        _rev: String::new(),
+        seats: 3,
+    };
+    assert_eq!(doc.id(), "chesterfield");
+    assert_eq!(doc.rev(), None);
+
+    doc.set_rev("1-abc".to_string());
+    doc.set_id("camelback".to_string());
+    assert_eq!(doc.rev(), Some("1-abc"));
+    assert_eq!(
+        serde_json::to_value(&doc).unwrap(),
+        json!({"_id": "camelback", "_rev": "1-abc", "seats": 3})
+    );
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Cushions {
+    count: u32,
+}
+
+#[test]
+fn should_use_marked_fields() {
+    let mut doc: Marked<Cushions> =
+        serde_json::from_value(json!({"_id": "camelback", "_rev": "2-def", "count": 4})).unwrap();
+    assert_eq!(doc.id(), "camelback");
+    assert_eq!(doc.rev(), Some("2-def"));
+    assert_eq!(doc.data, Cushions { count: 4 });
+
+    doc.set_rev("3-ghi".to_string());
+    assert_eq!(doc.revision, Some("3-ghi".to_string()));
+
+    doc.revision = None;
+    assert_eq!(doc.rev(), None);
+    assert_eq!(serde_json::to_value(&doc).unwrap(), json!({"_id": "camelback", "count": 4}));
+}
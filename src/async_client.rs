@@ -0,0 +1,595 @@
+use std::collections::HashMap;
+use std::mem;
+
+use futures::future::{self, Either};
+use futures::{Async, Future, Poll, Stream};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{self, to_string, Value};
+use tokio_core::reactor::Handle;
+
+use reqwest::unstable::async::{self, Decoder, RequestBuilder, Response};
+use reqwest::{self, Method, StatusCode, Url};
+
+use client::{encode_design_name, encode_doc_id};
+use client_builder::*;
+use document::*;
+use error::SofaError;
+use request::{response_meta, status_error, with_conflict_id};
+use types::*;
+
+/// Boxed future returned by every asynchronous operation of the crate
+pub type SofaFuture<T> = Box<dyn Future<Item = T, Error = SofaError>>;
+
+/// Sends a prepared request, forwarding the error the request could have been
+/// built with
+fn send(request: Result<RequestBuilder, SofaError>) -> SofaFuture<Response> {
+    match request {
+        Ok(mut req) => Box::new(req.send().from_err()),
+        Err(e) => Box::new(future::err(e)),
+    }
+}
+
+/// Passes a successful response through, or reads the error body CouchDB
+/// sent along an unsuccessful one into an `Err`
+fn ensure_success(res: Response) -> SofaFuture<Response> {
+    if res.status().is_success() {
+        return Box::new(future::ok(res));
+    }
+
+    let status = res.status();
+    let meta = response_meta(res.headers());
+    Box::new(
+        res.into_body()
+            .fold(Vec::new(), |mut body, chunk| {
+                body.extend_from_slice(&chunk);
+                Ok::<_, reqwest::Error>(body)
+            })
+            .then(move |body| Err(status_error(status, &body.unwrap_or_default()).with_meta(meta))),
+    )
+}
+
+/// Sends a prepared request and deserializes the JSON body of its successful
+/// response
+fn send_json<T: DeserializeOwned + 'static>(request: Result<RequestBuilder, SofaError>) -> SofaFuture<T> {
+    Box::new(send(request).and_then(ensure_success).and_then(|mut res| res.json::<T>().from_err()))
+}
+
+/// Sends a prepared request and resolves to `true` if the response status is
+/// one of the expected ones
+fn send_status(request: Result<RequestBuilder, SofaError>, expected: &'static [StatusCode]) -> SofaFuture<bool> {
+    Box::new(send(request).then(move |res| Ok(res.map(|res| expected.contains(&res.status())).unwrap_or(false))))
+}
+
+/// AsyncClient is the non-blocking counterpart of `Client`, built on top of
+/// reqwest's async client. It must be driven by a `tokio_core` reactor, and is
+/// configured through `ClientBuilder::build_async`.
+#[derive(Debug, Clone)]
+pub struct AsyncClient {
+    _client: async::Client,
+    _config: ClientConfig,
+    pub uri: String,
+    pub db_prefix: String,
+}
+
+impl AsyncClient {
+    /// Creates a client with the default configuration
+    pub fn new(uri: String, handle: &Handle) -> Result<AsyncClient, SofaError> {
+        ClientBuilder::new(uri).build_async(handle)
+    }
+
+    pub(crate) fn with_config(client: async::Client, config: ClientConfig, uri: String, db_prefix: String) -> AsyncClient {
+        AsyncClient {
+            _client: client,
+            _config: config,
+            uri,
+            db_prefix,
+        }
+    }
+
+    pub fn list_dbs(&self) -> SofaFuture<Vec<String>> {
+        send_json(self.get(s!("/_all_dbs"), None))
+    }
+
+    fn build_dbname(&self, dbname: &'static str) -> String {
+        self.db_prefix.clone() + dbname
+    }
+
+    pub fn db(&self, dbname: &'static str) -> SofaFuture<AsyncDatabase> {
+        let client = self.clone();
+        let db = AsyncDatabase::new(self.build_dbname(dbname), self.clone());
+
+        Box::new(
+            send(self.head(self.build_dbname(dbname), None)).and_then(move |res| match res.status() {
+                StatusCode::Ok => Either::A(future::ok(db)),
+                _ => Either::B(client.make_db(dbname)),
+            }),
+        )
+    }
+
+    pub fn make_db(&self, dbname: &'static str) -> SofaFuture<AsyncDatabase> {
+        let db = AsyncDatabase::new(self.build_dbname(dbname), self.clone());
+        let request = self.req(Method::Put, self.build_dbname(dbname), None);
+
+        Box::new(
+            send_json::<CouchResponse>(request).and_then(move |s| match s.ok {
+                Some(true) => Ok(db),
+                _ => {
+                    let err = s.error.unwrap_or(s!("unspecified error"));
+                    Err(SofaError::Custom(err))
+                }
+            }),
+        )
+    }
+
+    pub fn destroy_db(&self, dbname: &'static str) -> SofaFuture<bool> {
+        let request = self.delete(self.build_dbname(dbname), None);
+
+        // Destroying a database that doesn't exist is not an error
+        Box::new(send_json::<CouchResponse>(request).then(|result| match result {
+            Err(ref err) if err.is_not_found() => Ok(false),
+            result => Ok(result?.ok.unwrap_or(false)),
+        }))
+    }
+
+    pub fn check_status(&self) -> SofaFuture<CouchStatus> {
+        send_json(self.get(s!(""), None))
+    }
+
+    fn create_path(&self, path: String, args: Option<HashMap<String, String>>) -> Result<String, SofaError> {
+        let mut uri = Url::parse(&self.uri)?.join(&path)?;
+
+        if let Some(map) = args.as_ref().filter(|map| !map.is_empty()) {
+            let mut qp = uri.query_pairs_mut();
+            for (k, v) in map {
+                qp.append_pair(k, v);
+            }
+        }
+
+        Ok(uri.into_string())
+    }
+
+    pub fn req(
+        &self,
+        method: Method,
+        path: String,
+        opts: Option<HashMap<String, String>>,
+    ) -> Result<RequestBuilder, SofaError> {
+        let uri = self.create_path(path, opts)?;
+        let mut req = self._client.request(method, &uri);
+        req.headers(self._config.request_headers(&uri));
+
+        Ok(req)
+    }
+
+    pub fn get(&self, path: String, args: Option<HashMap<String, String>>) -> Result<RequestBuilder, SofaError> {
+        self.req(Method::Get, path, args)
+    }
+
+    pub fn post(&self, path: String, body: String) -> Result<RequestBuilder, SofaError> {
+        let mut req = self.req(Method::Post, path, None)?;
+        req.body(body);
+        Ok(req)
+    }
+
+    pub fn put(&self, path: String, body: String) -> Result<RequestBuilder, SofaError> {
+        let mut req = self.req(Method::Put, path, None)?;
+        req.body(body);
+        Ok(req)
+    }
+
+    pub fn head(&self, path: String, args: Option<HashMap<String, String>>) -> Result<RequestBuilder, SofaError> {
+        self.req(Method::Head, path, args)
+    }
+
+    pub fn delete(&self, path: String, args: Option<HashMap<String, String>>) -> Result<RequestBuilder, SofaError> {
+        self.req(Method::Delete, path, args)
+    }
+}
+
+/// AsyncDatabase is the non-blocking counterpart of `Database`. Every
+/// operation returns a `SofaFuture` instead of blocking on the response.
+#[derive(Debug, Clone)]
+pub struct AsyncDatabase {
+    _client: AsyncClient,
+    name: String,
+}
+
+impl AsyncDatabase {
+    pub fn new(name: String, client: AsyncClient) -> AsyncDatabase {
+        AsyncDatabase { _client: client, name }
+    }
+
+    fn create_document_path(&self, id: DocumentId) -> String {
+        let mut result: String = self.name.clone();
+        result.push('/');
+        result.push_str(&encode_doc_id(&id));
+        result
+    }
+
+    fn create_compact_path(&self, design_name: &'static str) -> String {
+        let mut result: String = self.name.clone();
+        result.push_str("/_compact/");
+        result.push_str(&encode_design_name(design_name));
+        result
+    }
+
+    /// Launches the compact process
+    pub fn compact(&self) -> SofaFuture<bool> {
+        let mut path: String = self.name.clone();
+        path.push_str("/_compact");
+
+        send_status(self._client.post(path, "".into()), &[StatusCode::Accepted])
+    }
+
+    /// Starts the compaction of all views
+    pub fn compact_views(&self) -> SofaFuture<bool> {
+        let mut path: String = self.name.clone();
+        path.push_str("/_view_cleanup");
+
+        send_status(self._client.post(path, "".into()), &[StatusCode::Accepted])
+    }
+
+    /// Starts the compaction of a given index
+    pub fn compact_index(&self, index: &'static str) -> SofaFuture<bool> {
+        send_status(
+            self._client.post(self.create_compact_path(index), "".into()),
+            &[StatusCode::Accepted],
+        )
+    }
+
+    /// Checks if a document ID exists
+    pub fn exists<I: Into<DocumentId>>(&self, id: I) -> SofaFuture<bool> {
+        send_status(
+            self._client.head(self.create_document_path(id.into()), None),
+            &[StatusCode::Ok, StatusCode::NotModified],
+        )
+    }
+
+    /// Gets one document
+    pub fn get<I: Into<DocumentId>>(&self, id: I) -> SofaFuture<Document> {
+        Box::new(send_json(self._client.get(self.create_document_path(id.into()), None)).map(Document::new))
+    }
+
+    /// Follows the changes of the database as they happen, through a
+    /// continuous feed, see `ChangesFeed`
+    pub fn changes_feed(&self, params: ChangesParams) -> AsyncChangesFeed {
+        AsyncChangesFeed::new(self.clone(), params)
+    }
+
+    fn changes_response(&self, params: &ChangesParams) -> SofaFuture<Response> {
+        let path = format!("{}/_changes", self.name);
+        let request = match params.filter.as_ref().and_then(|f| f.body()) {
+            Some(body) => self._client.req(Method::Post, path, Some(params.to_args())).map(|mut req| {
+                req.body(js!(body));
+                req
+            }),
+            None => self._client.get(path, Some(params.to_args())),
+        };
+
+        Box::new(send(request).and_then(ensure_success))
+    }
+
+    /// Gets documents in bulk with provided IDs list
+    pub fn get_bulk(&self, ids: Vec<DocumentId>) -> SofaFuture<DocumentCollection> {
+        self.get_bulk_params(ids, None)
+    }
+
+    /// Gets documents in bulk with provided IDs list, with added params. Parameters description can be found here: http://docs.couchdb.org/en/latest/api/ddoc/views.html#api-ddoc-view
+    pub fn get_bulk_params(
+        &self,
+        ids: Vec<DocumentId>,
+        params: Option<HashMap<String, String>>,
+    ) -> SofaFuture<DocumentCollection> {
+        let mut options = params.unwrap_or_default();
+        options.insert(s!("include_docs"), s!("true"));
+
+        let mut body = HashMap::new();
+        body.insert(s!("keys"), ids);
+
+        let request = to_string(&body).map_err(SofaError::from).and_then(|body| {
+            let mut req = self._client
+                .req(Method::Post, self.create_document_path("_all_docs".into()), Some(options))?;
+            req.body(body);
+            Ok(req)
+        });
+
+        Box::new(send_json(request).map(DocumentCollection::new))
+    }
+
+    /// Gets all the documents in database
+    pub fn get_all(&self) -> SofaFuture<DocumentCollection> {
+        self.get_all_params(None)
+    }
+
+    /// Gets all the documents in database, with applied parameters. Parameters description can be found here: http://docs.couchdb.org/en/latest/api/ddoc/views.html#api-ddoc-view
+    pub fn get_all_params(&self, params: Option<HashMap<String, String>>) -> SofaFuture<DocumentCollection> {
+        let mut options = params.unwrap_or_default();
+        options.insert(s!("include_docs"), s!("true"));
+
+        let request = self._client
+            .get(self.create_document_path("_all_docs".into()), Some(options));
+
+        Box::new(send_json(request).map(DocumentCollection::new))
+    }
+
+    /// Finds a document in the database through a Mango query. Parameters here http://docs.couchdb.org/en/latest/api/database/find.html
+    pub fn find<Q: Serialize>(&self, params: Q) -> SofaFuture<DocumentCollection> {
+        let path = self.create_document_path("_find".into());
+        let request = self._client.post(path, js!(&params));
+
+        Box::new(send_json::<FindResult>(request).and_then(|data| {
+            if let Some(doc_val) = data.docs {
+                let documents = doc_val
+                    .into_iter()
+                    // Remove _design documents
+                    .filter(|d| !d["_id"].as_str().unwrap_or_default().starts_with('_'))
+                    .map(Document::try_new)
+                    .collect::<Result<Vec<Document>, SofaError>>()?;
+
+                Ok(DocumentCollection::new_from_documents(documents))
+            } else if let Some(err) = data.error {
+                Err(SofaError::Custom(err))
+            } else {
+                Ok(DocumentCollection::default())
+            }
+        }))
+    }
+
+    /// Updates a document
+    pub fn save(&self, doc: Document) -> SofaFuture<Document> {
+        let id = doc._id.to_owned();
+        let raw = doc.get_data();
+
+        let request = to_string(&raw)
+            .map_err(SofaError::from)
+            .and_then(|body| self._client.put(self.create_document_path(id), body));
+
+        Box::new(
+            send_json::<DocumentCreatedResult>(request).and_then(move |data| match data.ok {
+                Some(true) => {
+                    let mut val = doc.get_data();
+                    val["_rev"] = json!(data.rev);
+
+                    Ok(Document::new(val))
+                }
+                _ => {
+                    let err = data.error.unwrap_or(s!("unspecified error"));
+                    Err(SofaError::Custom(err))
+                }
+            }),
+        )
+    }
+
+    /// Creates a document from a raw JSON document Value.
+    pub fn create(&self, raw_doc: Value) -> SofaFuture<Document> {
+        let request = to_string(&raw_doc)
+            .map_err(SofaError::from)
+            .and_then(|body| self._client.post(self.name.clone(), body));
+
+        Box::new(
+            send_json::<DocumentCreatedResult>(request).and_then(move |data| match data.ok {
+                Some(true) => {
+                    let data_id = match data.id {
+                        Some(id) => id,
+                        _ => return Err(SofaError::Custom(s!("invalid id"))),
+                    };
+
+                    let data_rev = match data.rev {
+                        Some(rev) => rev,
+                        _ => return Err(SofaError::Custom(s!("invalid rev"))),
+                    };
+
+                    let mut val = raw_doc;
+                    val["_id"] = json!(data_id);
+                    val["_rev"] = json!(data_rev);
+
+                    Ok(Document::new(val))
+                }
+                _ => {
+                    let err = data.error.unwrap_or(s!("unspecified error"));
+                    Err(SofaError::Custom(err))
+                }
+            }),
+        )
+    }
+
+    /// Removes a document from the database at its revision `rev`, resolving
+    /// to the revision of the deletion. Fails with a `SofaError::Conflict`
+    /// when `rev` isn't the current revision of the document.
+    pub fn remove<I: Into<DocumentId>>(&self, id: I, rev: &str) -> SofaFuture<String> {
+        let id: DocumentId = id.into();
+
+        let mut args = HashMap::new();
+        args.insert(s!("rev"), s!(rev));
+
+        let request = self._client.delete(self.create_document_path(id.clone()), Some(args));
+
+        Box::new(
+            send_json::<DocumentCreatedResult>(request)
+                .map_err(move |e| with_conflict_id(e, &id))
+                .and_then(|data| data.rev.ok_or_else(|| SofaError::Custom(s!("invalid rev")))),
+        )
+    }
+
+    /// Removes a document from the database at the revision it was read at,
+    /// see `remove`
+    pub fn remove_doc(&self, doc: &Document) -> SofaFuture<String> {
+        self.remove(doc._id.clone(), &doc._rev)
+    }
+
+    /// Inserts an index in a naive way, if it already exists, will resolve to
+    /// an `Err`
+    pub fn insert_index(&self, name: String, spec: IndexFields) -> SofaFuture<IndexCreated> {
+        let request = self._client.post(
+            self.create_document_path("_index".into()),
+            js!(json!({
+                "name": name,
+                "index": spec
+            })),
+        );
+
+        Box::new(send_json::<IndexCreated>(request).and_then(|data| {
+            if data.error.is_some() {
+                let err = data.error.unwrap_or(s!("unspecified error"));
+                Err(SofaError::Custom(err))
+            } else {
+                Ok(data)
+            }
+        }))
+    }
+
+    /// Reads the database's indexes and returns them
+    pub fn read_indexes(&self) -> SofaFuture<DatabaseIndexList> {
+        send_json(self._client.get(self.create_document_path("_index".into()), None))
+    }
+
+    /// Method to ensure an index is created on the database with the following
+    /// spec. Resolves to `true` when we created a new one, or `false` when the
+    /// index was already existing.
+    pub fn ensure_index(&self, name: String, spec: IndexFields) -> SofaFuture<bool> {
+        let db = self.clone();
+
+        Box::new(self.read_indexes().and_then(move |db_indexes| {
+            // We look for our index
+            if db_indexes.indexes.into_iter().any(|i| i.name == name) {
+                // Found? Ok let's return
+                return Either::A(future::ok(false));
+            }
+
+            // Let's create it then
+            Either::B(db.insert_index(name, spec).map(|_| true))
+        }))
+    }
+}
+
+enum FeedState {
+    Connecting(SofaFuture<Response>),
+    Reading(Box<Decoder>),
+    Done,
+}
+
+/// AsyncChangesFeed is the non-blocking counterpart of `ChangesFeed`: a
+/// `Stream` of the changes of a database, parsed line by line as chunks of
+/// the continuous feed arrive, reconnecting from the last sequence seen when
+/// the connection drops.
+pub struct AsyncChangesFeed {
+    db: AsyncDatabase,
+    params: ChangesParams,
+    state: FeedState,
+    buffer: Vec<u8>,
+    last_seq: Option<Seq>,
+    max_reconnects: u32,
+    reconnects: u32,
+}
+
+impl AsyncChangesFeed {
+    pub fn new(db: AsyncDatabase, mut params: ChangesParams) -> AsyncChangesFeed {
+        params.feed = Some(FeedMode::Continuous);
+        let state = FeedState::Connecting(db.changes_response(&params));
+
+        AsyncChangesFeed {
+            db,
+            params,
+            state,
+            buffer: Vec::new(),
+            last_seq: None,
+            max_reconnects: 3,
+            reconnects: 0,
+        }
+    }
+
+    /// Sets the number of reconnections attempted in a row, without any
+    /// change received in between, before giving up. Defaults to 3.
+    pub fn max_reconnects(mut self, max_reconnects: u32) -> AsyncChangesFeed {
+        self.max_reconnects = max_reconnects;
+        self
+    }
+
+    /// Last sequence seen, to resume the feed from later on
+    pub fn last_seq(&self) -> Option<&Seq> {
+        self.last_seq.as_ref()
+    }
+
+    fn reconnect(&mut self) -> FeedState {
+        self.buffer.clear();
+        self.reconnects += 1;
+
+        if self.reconnects > self.max_reconnects {
+            return FeedState::Done;
+        }
+
+        FeedState::Connecting(self.db.changes_response(&self.params))
+    }
+
+    /// Parses the next complete line of the buffer, if any
+    fn next_line(&mut self) -> Option<Result<Option<ChangeEvent>, SofaError>> {
+        let pos = self.buffer.iter().position(|b| *b == b'\n')?;
+        let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+
+        if line.iter().all(|b| b.is_ascii_whitespace()) {
+            return Some(Ok(None));
+        }
+
+        let value: Value = match serde_json::from_slice(&line) {
+            Ok(value) => value,
+            Err(e) => return Some(Err(e.into())),
+        };
+
+        if let Some(last_seq) = value.get("last_seq") {
+            self.last_seq = serde_json::from_value(last_seq.clone()).ok();
+            self.state = FeedState::Done;
+            self.buffer.clear();
+            return Some(Ok(None));
+        }
+
+        Some(serde_json::from_value(value).map(Some).map_err(SofaError::from))
+    }
+}
+
+impl Stream for AsyncChangesFeed {
+    type Item = ChangeEvent;
+    type Error = SofaError;
+
+    fn poll(&mut self) -> Poll<Option<ChangeEvent>, SofaError> {
+        loop {
+            match self.next_line() {
+                Some(Ok(Some(event))) => {
+                    self.params.since = Some(event.seq.clone());
+                    self.last_seq = Some(event.seq.clone());
+                    self.reconnects = 0;
+
+                    return Ok(Async::Ready(Some(event)));
+                }
+                Some(Ok(None)) => continue,
+                Some(Err(e)) => return Err(e),
+                None => {}
+            }
+
+            let next = match mem::replace(&mut self.state, FeedState::Done) {
+                FeedState::Done => return Ok(Async::Ready(None)),
+                FeedState::Connecting(mut response) => match response.poll() {
+                    Ok(Async::Ready(res)) => FeedState::Reading(Box::new(res.into_body())),
+                    Ok(Async::NotReady) => {
+                        self.state = FeedState::Connecting(response);
+                        return Ok(Async::NotReady);
+                    }
+                    Err(e) => return Err(e),
+                },
+                FeedState::Reading(mut body) => match body.poll() {
+                    Ok(Async::Ready(Some(chunk))) => {
+                        self.buffer.extend_from_slice(&chunk);
+                        FeedState::Reading(body)
+                    }
+                    Ok(Async::NotReady) => {
+                        self.state = FeedState::Reading(body);
+                        return Ok(Async::NotReady);
+                    }
+                    Ok(Async::Ready(None)) | Err(_) => self.reconnect(),
+                },
+            };
+
+            self.state = next;
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use reqwest::header::{ContentLength, ContentType, ETag};
+use reqwest::{Body, Response};
+
+use document::*;
+use error::SofaError;
+
+/// Contents of an attachment to upload, either held in memory or read out of
+/// a reader as they are sent
+pub enum AttachmentBody {
+    Bytes(Vec<u8>),
+    /// Reader streamed to the server, along with its length when known.
+    /// Without it, the contents are sent with a chunked transfer encoding.
+    Reader(Box<dyn Read + Send>, Option<u64>),
+}
+
+impl AttachmentBody {
+    pub fn reader<R: Read + Send + 'static>(reader: R, length: Option<u64>) -> AttachmentBody {
+        AttachmentBody::Reader(Box::new(reader), length)
+    }
+
+    pub(crate) fn into_body(self) -> Body {
+        match self {
+            AttachmentBody::Bytes(bytes) => Body::from(bytes),
+            AttachmentBody::Reader(reader, Some(length)) => Body::sized(reader, length),
+            AttachmentBody::Reader(reader, None) => Body::new(reader),
+        }
+    }
+}
+
+impl fmt::Debug for AttachmentBody {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AttachmentBody::Bytes(ref bytes) => write!(f, "AttachmentBody::Bytes({} bytes)", bytes.len()),
+            AttachmentBody::Reader(_, length) => write!(f, "AttachmentBody::Reader({:?})", length),
+        }
+    }
+}
+
+/// AttachmentReader streams the contents of an attachment as they are
+/// downloaded, see `Database::get_attachment`
+#[derive(Debug)]
+pub struct AttachmentReader {
+    response: Response,
+}
+
+impl AttachmentReader {
+    pub fn new(response: Response) -> AttachmentReader {
+        AttachmentReader { response }
+    }
+
+    /// Content type the attachment was uploaded with
+    pub fn content_type(&self) -> Option<String> {
+        self.response.headers().get::<ContentType>().map(|ct| ct.to_string())
+    }
+
+    /// Length of the attachment, in bytes, unless sent compressed
+    pub fn length(&self) -> Option<u64> {
+        self.response.headers().get::<ContentLength>().map(|length| length.0)
+    }
+
+    /// Digest of the attachment, e.g. `md5-...`
+    pub fn digest(&self) -> Option<String> {
+        self.response.headers().get::<ETag>().map(|etag| s!(etag.tag()))
+    }
+
+    /// Writes the rest of the attachment into `writer`, returning the number
+    /// of bytes written
+    pub fn copy_to<W: Write + ?Sized>(&mut self, writer: &mut W) -> io::Result<u64> {
+        io::copy(self, writer)
+    }
+}
+
+impl Read for AttachmentReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.response.read(buf)
+    }
+}
+
+impl From<Vec<u8>> for AttachmentBody {
+    fn from(bytes: Vec<u8>) -> AttachmentBody {
+        AttachmentBody::Bytes(bytes)
+    }
+}
+
+impl<'a> From<&'a [u8]> for AttachmentBody {
+    fn from(bytes: &'a [u8]) -> AttachmentBody {
+        AttachmentBody::Bytes(bytes.to_vec())
+    }
+}
+
+impl From<String> for AttachmentBody {
+    fn from(text: String) -> AttachmentBody {
+        AttachmentBody::Bytes(text.into_bytes())
+    }
+}
+
+impl<'a> From<&'a str> for AttachmentBody {
+    fn from(text: &'a str) -> AttachmentBody {
+        AttachmentBody::Bytes(text.as_bytes().to_vec())
+    }
+}
+
+/// Attachment sent or received along its document in a single
+/// `multipart/related` request
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttachmentPart {
+    pub name: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+impl AttachmentPart {
+    pub fn new<D: Into<Vec<u8>>>(name: &str, content_type: &str, data: D) -> AttachmentPart {
+        AttachmentPart {
+            name: s!(name),
+            content_type: s!(content_type),
+            data: data.into(),
+        }
+    }
+}
+
+/// Document fetched along with the contents of its attachments, see
+/// `Database::get_with_attachments`
+#[derive(Debug, Clone)]
+pub struct MultipartDocument {
+    pub doc: Document,
+    pub attachments: Vec<AttachmentPart>,
+}
+
+/// Part of a `multipart/related` body
+pub(crate) struct MultipartPart {
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl MultipartPart {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Reads the boundary parameter of a multipart content type
+pub(crate) fn multipart_boundary(content_type: &str) -> Option<String> {
+    content_type
+        .split(';')
+        .filter_map(|param| param.trim().split_once('='))
+        .find(|&(key, _)| key.trim().eq_ignore_ascii_case("boundary"))
+        .map(|(_, value)| s!(value.trim().trim_matches('"')))
+}
+
+/// Reads the file name of a `Content-Disposition` header
+pub(crate) fn disposition_filename(disposition: &str) -> Option<String> {
+    disposition
+        .split(';')
+        .filter_map(|param| param.trim().split_once('='))
+        .find(|&(key, _)| key.trim().eq_ignore_ascii_case("filename"))
+        .map(|(_, value)| s!(value.trim().trim_matches('"')))
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    if from > haystack.len() {
+        return None;
+    }
+
+    haystack[from..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|i| i + from)
+}
+
+/// Splits a `multipart/related` body into its parts
+pub(crate) fn parse_multipart(boundary: &str, body: &[u8]) -> Result<Vec<MultipartPart>, SofaError> {
+    let delimiter = format!("--{}", boundary).into_bytes();
+    let separator = format!("\r\n--{}", boundary).into_bytes();
+    let malformed = || SofaError::Custom(s!("malformed multipart body"));
+
+    let mut parts = Vec::new();
+    let mut position = find_bytes(body, &delimiter, 0).ok_or_else(malformed)? + delimiter.len();
+
+    loop {
+        if body[position..].starts_with(b"--") {
+            return Ok(parts);
+        }
+        if body[position..].starts_with(b"\r\n") {
+            position += 2;
+        }
+
+        let end = find_bytes(body, &separator, position).ok_or_else(malformed)?;
+        let part = &body[position..end];
+        let (head, part_body) = match find_bytes(part, b"\r\n\r\n", 0) {
+            Some(i) => (&part[..i], &part[i + 4..]),
+            None if part.starts_with(b"\r\n") => (&part[..0], &part[2..]),
+            None => return Err(malformed()),
+        };
+
+        let headers = String::from_utf8_lossy(head)
+            .split("\r\n")
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (s!(name.trim()), s!(value.trim())))
+            .collect();
+        parts.push(MultipartPart {
+            headers,
+            body: part_body.to_vec(),
+        });
+
+        position = end + separator.len();
+    }
+}
+
+/// Picks a boundary that appears in none of the parts of a multipart body
+pub(crate) fn pick_boundary(parts: &[&[u8]]) -> String {
+    let mut n: u32 = 0;
+
+    loop {
+        let boundary = format!("sofa-boundary-{:08x}", n);
+        if !parts.iter().any(|part| find_bytes(part, boundary.as_bytes(), 0).is_some()) {
+            return boundary;
+        }
+        n += 1;
+    }
+}
+
+/// Writes a `multipart/related` body made of a JSON document followed by
+/// the data of each attachment
+pub(crate) fn write_multipart(boundary: &str, doc: &[u8], attachments: &[AttachmentPart]) -> Vec<u8> {
+    let mut body = Vec::new();
+
+    body.extend_from_slice(format!("--{}\r\nContent-Type: application/json\r\n\r\n", boundary).as_bytes());
+    body.extend_from_slice(doc);
+
+    for attachment in attachments {
+        body.extend_from_slice(format!("\r\n--{}\r\n", boundary).as_bytes());
+        body.extend_from_slice(
+            format!(
+                "Content-Disposition: attachment; filename=\"{}\"\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n",
+                attachment.name,
+                attachment.content_type,
+                attachment.data.len()
+            ).as_bytes(),
+        );
+        body.extend_from_slice(&attachment.data);
+    }
+
+    body.extend_from_slice(format!("\r\n--{}--", boundary).as_bytes());
+    body
+}
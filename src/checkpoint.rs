@@ -0,0 +1,107 @@
+use std::sync::Mutex;
+
+use serde_json;
+
+use database::*;
+use error::SofaError;
+use feed::*;
+use types::*;
+
+/// ChangesCheckpointer stores the sequence up to which a consumer processed
+/// the changes of a database in a local document, so that it can resume from
+/// there after a restart. Saving the sequence of an event only once it was
+/// processed gives at-least-once processing: events processed after the last
+/// checkpoint are delivered again on resume.
+#[derive(Debug)]
+pub struct ChangesCheckpointer {
+    db: Database,
+    id: String,
+    rev: Mutex<Option<String>>,
+}
+
+impl ChangesCheckpointer {
+    /// Creates a checkpointer storing its sequence in the `_local/{consumer}`
+    /// document of `db`
+    pub fn new(db: Database, consumer: &str) -> ChangesCheckpointer {
+        ChangesCheckpointer {
+            db,
+            id: s!(consumer),
+            rev: Mutex::new(None),
+        }
+    }
+
+    fn set_rev(&self, rev: Option<String>) -> Result<(), SofaError> {
+        let mut guard = self.rev.lock().map_err(|_| SofaError::Custom(s!("checkpoint lock poisoned")))?;
+        *guard = rev;
+
+        Ok(())
+    }
+
+    /// Reads the last checkpointed sequence, `None` when nothing was
+    /// checkpointed yet. Any other failure to read the checkpoint is an
+    /// error, rather than a reason to start over.
+    pub fn load(&self) -> Result<Option<Seq>, SofaError> {
+        let doc = match self.db.get_local(&self.id) {
+            Ok(doc) => doc,
+            Err(ref e) if e.is_not_found() => {
+                self.set_rev(None)?;
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+        self.set_rev(Some(doc._rev.clone()))?;
+
+        match doc.get_data().get("seq") {
+            Some(seq) => Ok(Some(serde_json::from_value(seq.clone())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Checkpoints a sequence, typically the one of the last processed event
+    pub fn save(&self, seq: &Seq) -> Result<(), SofaError> {
+        let rev = self.rev.lock().map_err(|_| SofaError::Custom(s!("checkpoint lock poisoned")))?.clone();
+
+        let saved = match self.write(seq, rev) {
+            Ok(doc) => doc,
+            Err(ref e) if e.is_conflict() => {
+                // The cached revision may be stale, when another process
+                // checkpointed in between
+                self.load()?;
+                let rev = self.rev.lock().map_err(|_| SofaError::Custom(s!("checkpoint lock poisoned")))?.clone();
+                self.write(seq, rev)?
+            }
+            Err(e) => return Err(e),
+        };
+
+        self.set_rev(Some(saved._rev))
+    }
+
+    fn write(&self, seq: &Seq, rev: Option<String>) -> Result<::document::Document, SofaError> {
+        let mut doc = json!({ "seq": seq });
+        if let Some(rev) = rev {
+            doc["_rev"] = json!(rev);
+        }
+
+        self.db.save_local(&self.id, doc)
+    }
+
+    /// Follows the changes of the database from the last checkpointed
+    /// sequence, or from `params.since` when nothing was checkpointed yet
+    pub fn feed(&self, mut params: ChangesParams) -> Result<ChangesFeed, SofaError> {
+        if let Some(seq) = self.load()? {
+            params.since = Some(seq);
+        }
+
+        Ok(self.db.changes_feed(params))
+    }
+
+    /// Fetches the changes that happened since the last checkpointed
+    /// sequence, in normal or longpoll mode
+    pub fn changes(&self, mut params: ChangesParams) -> Result<Changes, SofaError> {
+        if let Some(seq) = self.load()? {
+            params.since = Some(seq);
+        }
+
+        self.db.changes(&params)
+    }
+}
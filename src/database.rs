@@ -1,13 +1,24 @@
 use std::collections::HashMap;
+use std::io::Read;
+use std::time::Duration;
 
-use reqwest::StatusCode;
+use reqwest::header::{Accept, ContentType, ETag, EntityTag, Headers, IfNoneMatch};
+use reqwest::{Method, Response, StatusCode};
 
-use failure::Error;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use serde_json;
-use serde_json::{from_reader, to_string, Value};
+use serde_json::{to_string, Value};
 
+use attachment::*;
+use checkpoint::*;
 use client::*;
 use document::*;
+use feed::*;
+use pagination::*;
+use partition::*;
+use request::*;
+use typed_document::*;
 use error::SofaError;
 use types::*;
 
@@ -23,14 +34,130 @@ impl Database {
     pub fn new(name: String, client: Client) -> Database {
         Database {
             _client: client,
-            name: name,
+            name,
         }
     }
 
+    /// Returns a handle on this database whose requests time out after `to`,
+    /// see `Client::with_timeout`
+    pub fn with_timeout(&self, to: Duration) -> Result<Database, SofaError> {
+        Ok(Database::new(self.name.clone(), self._client.with_timeout(to)?))
+    }
+
+    /// Fetches the metadata of the database: document counts, sizes,
+    /// sequences and properties
+    pub fn info(&self) -> Result<DbInfo, SofaError> {
+        let response = self._client.get(self.name.clone(), None)?.send()?;
+
+        read_json(response)
+    }
+
+    /// Fetches the security object of the database
+    pub fn get_security(&self) -> Result<SecurityDocument, SofaError> {
+        let response = self._client.get(format!("{}/_security", self.name), None)?.send()?;
+
+        read_json(response)
+    }
+
+    /// Replaces the security object of the database
+    pub fn set_security(&self, security: SecurityDocument) -> Result<bool, SofaError> {
+        let response = self._client.put(format!("{}/_security", self.name), js!(security))?.send()?;
+        let s: CouchResponse = read_json(response)?;
+
+        Ok(s.ok.unwrap_or(false))
+    }
+
+    /// Permanently removes revisions of documents, `revs` listing the
+    /// revisions to purge by document ID. Unlike deleted documents, purged
+    /// revisions are neither kept nor replicated.
+    pub fn purge(&self, revs: HashMap<String, Vec<String>>) -> Result<PurgeResult, SofaError> {
+        let response = self._client.post(format!("{}/_purge", self.name), js!(revs))?.send()?;
+
+        read_json(response)
+    }
+
+    /// Fetches the number of purges the database keeps track of, for
+    /// replicas and indexes to catch up with them
+    pub fn get_purged_infos_limit(&self) -> Result<u64, SofaError> {
+        let response = self._client.get(format!("{}/_purged_infos_limit", self.name), None)?.send()?;
+
+        read_json(response)
+    }
+
+    /// Sets the number of purges the database keeps track of
+    pub fn set_purged_infos_limit(&self, limit: u64) -> Result<bool, SofaError> {
+        let response = self._client
+            .put(format!("{}/_purged_infos_limit", self.name), js!(limit))?
+            .send()?;
+        let s: CouchResponse = read_json(response)?;
+
+        Ok(s.ok.unwrap_or(false))
+    }
+
+    /// Fetches the number of revisions of each document the database keeps
+    /// track of
+    pub fn get_revs_limit(&self) -> Result<u64, SofaError> {
+        let response = self._client.get(format!("{}/_revs_limit", self.name), None)?.send()?;
+
+        read_json(response)
+    }
+
+    /// Sets the number of revisions of each document the database keeps
+    /// track of. Lower limits save space but make conflicts more likely when
+    /// replicas are out of sync for long.
+    pub fn set_revs_limit(&self, limit: u64) -> Result<bool, SofaError> {
+        let response = self._client.put(format!("{}/_revs_limit", self.name), js!(limit))?.send()?;
+        let s: CouchResponse = read_json(response)?;
+
+        Ok(s.ok.unwrap_or(false))
+    }
+
+    /// Compares revisions, listed by document ID, with the ones of the
+    /// database, returning the missing ones along with their possible
+    /// ancestors. Documents whose revisions are all known are left out.
+    pub fn revs_diff(&self, revs: HashMap<String, Vec<String>>) -> Result<HashMap<String, RevsDiff>, SofaError> {
+        let response = self._client.post(format!("{}/_revs_diff", self.name), js!(revs))?.send()?;
+
+        read_json(response)
+    }
+
+    /// Compares revisions, listed by document ID, with the ones of the
+    /// database, returning the missing ones
+    pub fn missing_revs(&self, revs: HashMap<String, Vec<String>>) -> Result<HashMap<String, Vec<String>>, SofaError> {
+        let response = self._client.post(format!("{}/_missing_revs", self.name), js!(revs))?.send()?;
+        let data: MissingRevs = read_json(response)?;
+
+        Ok(data.missing_revs)
+    }
+
+    /// Lists the shards of the database and the nodes holding them
+    pub fn shards(&self) -> Result<DbShards, SofaError> {
+        let response = self._client.get(format!("{}/_shards", self.name), None)?.send()?;
+
+        read_json(response)
+    }
+
+    /// Finds the shard a document ID belongs to
+    pub fn shard_for_doc(&self, id: &str) -> Result<DocumentShard, SofaError> {
+        let path = format!("{}/_shards/{}", self.name, encode_path_segment(id));
+        let response = self._client.get(path, None)?.send()?;
+
+        read_json(response)
+    }
+
+    /// Forces the synchronization of the copies of every shard of the
+    /// database
+    pub fn sync_shards(&self) -> Result<bool, SofaError> {
+        let response = self._client.post(format!("{}/_sync_shards", self.name), s!(""))?.send()?;
+        let s: CouchResponse = read_json(response)?;
+
+        Ok(s.ok.unwrap_or(false))
+    }
+
     fn create_document_path(&self, id: DocumentId) -> String {
         let mut result: String = self.name.clone();
-        result.push_str("/");
-        result.push_str(&id);
+        result.push('/');
+        result.push_str(&encode_doc_id(&id));
         result
     }
 
@@ -38,14 +165,14 @@ impl Database {
     fn create_design_path(&self, id: DocumentId) -> String {
         let mut result: String = self.name.clone();
         result.push_str("/_design/");
-        result.push_str(&id);
+        result.push_str(&encode_path_segment(&id));
         result
     }
 
-    fn create_compact_path(&self, design_name: &'static str) -> String {
+    fn create_compact_path(&self, design_name: &str) -> String {
         let mut result: String = self.name.clone();
         result.push_str("/_compact/");
-        result.push_str(design_name);
+        result.push_str(&encode_design_name(design_name));
         result
     }
 
@@ -57,10 +184,10 @@ impl Database {
         let request = self._client.post(path, "".into());
 
         request
-            .and_then(|mut req| {
-                Ok(req.send()
-                    .and_then(|res| Ok(res.status() == StatusCode::Accepted))
-                    .unwrap_or(false))
+            .map(|mut req| {
+                req.send()
+                    .map(|res| res.status() == StatusCode::Accepted)
+                    .unwrap_or(false)
             })
             .unwrap_or(false)
     }
@@ -73,54 +200,166 @@ impl Database {
         let request = self._client.post(path, "".into());
 
         request
-            .and_then(|mut req| {
-                Ok(req.send()
-                    .and_then(|res| Ok(res.status() == StatusCode::Accepted))
-                    .unwrap_or(false))
+            .map(|mut req| {
+                req.send()
+                    .map(|res| res.status() == StatusCode::Accepted)
+                    .unwrap_or(false)
             })
             .unwrap_or(false)
     }
 
-    /// Starts the compaction of a given index
+    /// Starts the compaction of a given index, the view index of the design
+    /// document `index`, see `compact_view`
     pub fn compact_index(&self, index: &'static str) -> bool {
-        let request = self._client.post(self.create_compact_path(index), "".into());
+        self.compact_view(index)
+    }
+
+    /// Starts the compaction of the views of a design document
+    pub fn compact_view(&self, ddoc: &str) -> bool {
+        let request = self._client.post(self.create_compact_path(ddoc), "".into());
 
         request
-            .and_then(|mut req| {
-                Ok(req.send()
-                    .and_then(|res| Ok(res.status() == StatusCode::Accepted))
-                    .unwrap_or(false))
+            .map(|mut req| {
+                req.send()
+                    .map(|res| res.status() == StatusCode::Accepted)
+                    .unwrap_or(false)
             })
             .unwrap_or(false)
     }
 
+    /// Removes the index files no design document refers to anymore
+    pub fn view_cleanup(&self) -> bool {
+        self.compact_views()
+    }
+
+    /// Lists the compactions of the database, or of its views, currently
+    /// running on the server
+    pub fn compaction_tasks(&self) -> Result<Vec<ActiveTask>, SofaError> {
+        let tasks = self._client.active_tasks()?;
+
+        Ok(tasks
+            .into_iter()
+            .filter(|t| t.task_type == "database_compaction" || t.task_type == "view_compaction")
+            .filter(|t| t.database.as_ref().is_some_and(|db| task_database_is(db, &self.name)))
+            .collect())
+    }
+
+    /// Reports the progress of the running compactions of the database, as
+    /// the average completion percentage of their tasks, or `None` when no
+    /// compaction is running. Meant to be polled until it returns `None`.
+    pub fn compaction_progress(&self) -> Result<Option<u64>, SofaError> {
+        let tasks = self.compaction_tasks()?;
+
+        if tasks.is_empty() {
+            return Ok(None);
+        }
+
+        let total: u64 = tasks.iter().map(|t| t.progress.unwrap_or(0)).sum();
+        Ok(Some(total / tasks.len() as u64))
+    }
+
     /// Checks if a document ID exists
-    pub fn exists(&self, id: DocumentId) -> bool {
-        let request = self._client.head(self.create_document_path(id), None);
+    pub fn exists<I: Into<DocumentId>>(&self, id: I) -> bool {
+        let request = self._client.head(self.create_document_path(id.into()), None);
 
         request
-            .and_then(|mut req| {
-                Ok(req.send()
-                    .and_then(|res| {
-                        Ok(match res.status() {
-                            StatusCode::Ok | StatusCode::NotModified => true,
-                            _ => false,
-                        })
-                    })
-                    .unwrap_or(false))
+            .map(|mut req| {
+                req.send()
+                    .map(|res| matches!(res.status(), StatusCode::Ok | StatusCode::NotModified))
+                    .unwrap_or(false)
             })
             .unwrap_or(false)
     }
 
+    /// Checks if a document exists, through a `HEAD` request which doesn't
+    /// download it. Unlike `exists`, errors are reported instead of being
+    /// taken for a missing document.
+    pub fn doc_exists<I: Into<DocumentId>>(&self, id: I) -> Result<bool, SofaError> {
+        Ok(self.get_current_rev(id)?.is_some())
+    }
+
+    /// Gets the current revision of a document out of the `ETag` of a `HEAD`
+    /// request, `None` when the document doesn't exist
+    pub fn get_current_rev<I: Into<DocumentId>>(&self, id: I) -> Result<Option<String>, SofaError> {
+        let response = self._client.head(self.create_document_path(id.into()), None)?.send()?;
+
+        match response.status() {
+            StatusCode::Ok => {
+                let etag = response.headers().get::<ETag>().ok_or_else(|| SofaError::Custom(s!("missing ETag")))?;
+                Ok(Some(s!(etag.tag())))
+            }
+            StatusCode::NotFound => Ok(None),
+            _ => Err(response_error(response)),
+        }
+    }
+
     /// Gets one document
-    pub fn get(&self, id: DocumentId) -> Result<Document, Error> {
-        let response = self._client.get(self.create_document_path(id), None)?.send()?;
+    pub fn get<I: Into<DocumentId>>(&self, id: I) -> Result<Document, SofaError> {
+        Ok(self.get_with_meta(id)?.into_inner())
+    }
+
+    /// Gets one document along with the metadata of the response: the
+    /// request ID the server logged it under, and its revision as `ETag`
+    pub fn get_with_meta<I: Into<DocumentId>>(&self, id: I) -> Result<WithMeta<Document>, SofaError> {
+        let response = self._client.get(self.create_document_path(id.into()), None)?.send()?;
+        let data: WithMeta<Value> = read_json_with_meta(response)?;
+
+        Ok(WithMeta {
+            value: Document::new(data.value),
+            meta: data.meta,
+        })
+    }
+
+    /// Gets one document unless it's still at the known revision `rev`, the
+    /// request being sent with an `If-None-Match` header, so that cached
+    /// documents can be validated without being downloaded again
+    pub fn get_if_none_match<I: Into<DocumentId>>(&self, id: I, rev: &str) -> Result<Conditional<Document>, SofaError> {
+        let response = self._client
+            .get(self.create_document_path(id.into()), None)?
+            .header(IfNoneMatch::Items(vec![EntityTag::strong(s!(rev))]))
+            .send()?;
+
+        if response.status() == StatusCode::NotModified {
+            return Ok(Conditional::NotModified);
+        }
+
+        Ok(Conditional::Modified(Document::new(read_json(response)?)))
+    }
+
+    /// Gets one document at a given revision
+    pub fn get_rev<I: Into<DocumentId>>(&self, id: I, rev: &str) -> Result<Document, SofaError> {
+        self.get_params(id, &GetDocParams {
+            rev: Some(s!(rev)),
+            ..Default::default()
+        })
+    }
+
+    /// Gets one document with applied parameters, e.g. to include its
+    /// revision metadata, read through `Document::revisions`
+    pub fn get_params<I: Into<DocumentId>>(&self, id: I, params: &GetDocParams) -> Result<Document, SofaError> {
+        let response = self._client
+            .get(self.create_document_path(id.into()), Some(params.to_args()))?
+            .send()?;
 
-        Ok(Document::new(from_reader(response)?))
+        Ok(Document::new(read_json(response)?))
+    }
+
+    /// Gets leaf revisions of a document: the given ones, or all of them,
+    /// conflicting revisions included, when `revs` is `None`
+    pub fn get_open_revs<I: Into<DocumentId>>(&self, id: I, revs: Option<Vec<String>>) -> Result<Vec<OpenRevision>, SofaError> {
+        let mut args = HashMap::new();
+        args.insert(s!("open_revs"), revs.map_or(s!("all"), |revs| js!(revs)));
+
+        let response = self._client
+            .get(self.create_document_path(id.into()), Some(args))?
+            .header(Accept::json())
+            .send()?;
+
+        read_json(response)
     }
 
     /// Gets documents in bulk with provided IDs list
-    pub fn get_bulk(&self, ids: Vec<DocumentId>) -> Result<DocumentCollection, Error> {
+    pub fn get_bulk(&self, ids: Vec<DocumentId>) -> Result<DocumentCollection, SofaError> {
         self.get_bulk_params(ids, None)
     }
 
@@ -129,7 +368,7 @@ impl Database {
         &self,
         ids: Vec<DocumentId>,
         params: Option<HashMap<String, String>>,
-    ) -> Result<DocumentCollection, Error> {
+    ) -> Result<DocumentCollection, SofaError> {
         let mut options;
         if let Some(opts) = params {
             options = opts;
@@ -143,20 +382,295 @@ impl Database {
         body.insert(s!("keys"), ids);
 
         let response = self._client
-            .get(self.create_document_path("_all_docs".into()), Some(options))?
+            .req(Method::Post, self.create_document_path("_all_docs".into()), Some(options))?
             .body(to_string(&body)?)
             .send()?;
 
-        Ok(DocumentCollection::new(from_reader(response)?))
+        Ok(DocumentCollection::new(read_json(response)?))
+    }
+
+    /// Gets documents by ID in one request, deserialized into a struct of your
+    /// own, telling found documents from deleted and missing ones
+    pub fn get_bulk_typed<T: DeserializeOwned, I: Into<DocumentId>>(
+        &self,
+        ids: Vec<I>,
+    ) -> Result<HashMap<DocumentId, FetchedDoc<T>>, SofaError> {
+        let keys: Vec<String> = ids.into_iter().map(|id| s!(id.into())).collect();
+        if keys.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let params = AllDocsParams {
+            include_docs: Some(true),
+            ..Default::default()
+        };
+
+        self.all_docs_keys(keys, &params)?
+            .rows
+            .into_iter()
+            .map(|row| {
+                let id = s!(row.key.as_str().unwrap_or_default());
+                let fetched = match (row.value, row.doc) {
+                    (Some(ref value), _) if value.deleted == Some(true) => FetchedDoc::Deleted {
+                        rev: value.rev.clone(),
+                    },
+                    (Some(_), Some(doc)) => FetchedDoc::Found(serde_json::from_value(doc)?),
+                    _ => FetchedDoc::Missing,
+                };
+
+                Ok((DocumentId::from(id), fetched))
+            })
+            .collect()
+    }
+
+    /// Fetches documents, or specific revisions of documents, in one request.
+    /// Each result lists the fetched revisions of a document, or the reason
+    /// why they could not be.
+    pub fn bulk_get(&self, items: Vec<BulkGetItem>) -> Result<Vec<BulkGetResult>, SofaError> {
+        let response = self._client
+            .post(format!("{}/_bulk_get", self.name), js!(json!({ "docs": items })))?
+            .send()?;
+        let data: BulkGetResponse = read_json(response)?;
+
+        Ok(data.results)
+    }
+
+    /// Writes documents in one request, returning the outcome of each write
+    /// in the order of `docs`. Documents carrying `"_deleted": true` along
+    /// with their `_rev` are deleted. A conflicting write doesn't fail the
+    /// others, its `error` being set instead.
+    pub fn bulk_docs(&self, docs: Vec<Value>) -> Result<Vec<DocumentCreatedResult>, SofaError> {
+        self.bulk_docs_params(docs, BulkDocsParams::default())
+    }
+
+    /// Writes documents in one request, with added params, see `bulk_docs`.
+    /// Setting `new_edits` to `false` writes documents with their existing
+    /// revisions, to migrate or restore a database.
+    pub fn bulk_docs_params(&self, docs: Vec<Value>, params: BulkDocsParams) -> Result<Vec<DocumentCreatedResult>, SofaError> {
+        let mut body = json!(params);
+        body["docs"] = json!(docs);
+
+        let response = self._client.post(format!("{}/_bulk_docs", self.name), js!(body))?.send()?;
+
+        read_json(response)
+    }
+
+    /// Writes documents in one request whether they exist or not: the current
+    /// revisions of the documents holding an `_id` are fetched first, and
+    /// replace the `_rev` they may carry. Outcomes are returned in the order
+    /// of `docs`, as with `bulk_docs`.
+    pub fn bulk_upsert(&self, mut docs: Vec<Value>) -> Result<Vec<DocumentCreatedResult>, SofaError> {
+        let ids: Vec<String> = docs.iter().filter_map(|doc| doc["_id"].as_str().map(String::from)).collect();
+        let revs = self.current_revs(ids)?;
+
+        for doc in docs.iter_mut() {
+            let rev = doc["_id"].as_str().and_then(|id| revs.get(id)).cloned();
+            match (rev, doc.as_object_mut()) {
+                (Some(rev), Some(doc)) => {
+                    doc.insert(s!("_rev"), json!(rev));
+                }
+                (None, Some(doc)) => {
+                    doc.remove("_rev");
+                }
+                _ => {}
+            }
+        }
+
+        self.bulk_docs(docs)
+    }
+
+    /// Removes documents in one request, given by ID or along their revision,
+    /// e.g. as `&Document`. The current revisions of the documents given by
+    /// ID only are fetched first. Outcomes are returned in the order of
+    /// `items`, documents that don't exist getting a `not_found` error.
+    pub fn bulk_remove<I: Into<BulkRemoveItem>>(&self, items: Vec<I>) -> Result<Vec<DocumentCreatedResult>, SofaError> {
+        let items: Vec<BulkRemoveItem> = items.into_iter().map(Into::into).collect();
+        let unknown = items.iter().filter(|item| item.rev.is_none()).map(|item| item.id.clone()).collect();
+        let revs = self.current_revs(unknown)?;
+
+        let mut results: Vec<Option<DocumentCreatedResult>> = Vec::with_capacity(items.len());
+        let mut tombstones = Vec::new();
+
+        for BulkRemoveItem { id, rev } in items {
+            match rev.or_else(|| revs.get(&id).cloned()) {
+                Some(rev) => {
+                    tombstones.push(json!({ "_id": id, "_rev": rev, "_deleted": true }));
+                    results.push(None);
+                }
+                None => results.push(Some(DocumentCreatedResult {
+                    id: Some(id),
+                    ok: None,
+                    rev: None,
+                    error: Some(s!("not_found")),
+                    reason: Some(s!("missing")),
+                })),
+            }
+        }
+
+        let mut written = if tombstones.is_empty() {
+            vec![]
+        } else {
+            self.bulk_docs(tombstones)?
+        }.into_iter();
+
+        Ok(results
+            .into_iter()
+            .filter_map(|result| result.or_else(|| written.next()))
+            .collect())
+    }
+
+    /// Lists the documents having conflicting revisions, read from
+    /// `_all_docs` with `conflicts` set. All the documents are fetched by
+    /// this single request, which suits small databases: larger ones would
+    /// rather index their conflicts in a view.
+    pub fn list_conflicts(&self) -> Result<Vec<DocumentConflicts>, SofaError> {
+        let params = AllDocsParams {
+            include_docs: Some(true),
+            conflicts: Some(true),
+            ..Default::default()
+        };
+
+        let mut conflicts = Vec::new();
+        for row in self.all_docs(&params)?.rows {
+            let doc = match row.doc {
+                Some(doc) => Document::new(doc),
+                None => continue,
+            };
+
+            let revisions = doc.revisions()?;
+            if !revisions.conflicts.is_empty() {
+                conflicts.push(DocumentConflicts {
+                    rev: doc.rev()?,
+                    id: doc._id.into_string(),
+                    conflicts: revisions.conflicts,
+                });
+            }
+        }
+
+        Ok(conflicts)
+    }
+
+    /// Resolves the conflicts of a document in one `_bulk_docs` request: the
+    /// `losers` revisions are deleted, and `winner`, when given, is written
+    /// on top of the revision its `_rev` holds, e.g. to store merged
+    /// contents. Outcomes are returned in the order of the writes, `winner`
+    /// first.
+    pub fn resolve_conflict<I: Into<DocumentId>>(
+        &self,
+        id: I,
+        winner: Option<Value>,
+        losers: &[Rev],
+    ) -> Result<Vec<DocumentCreatedResult>, SofaError> {
+        let id: DocumentId = id.into();
+
+        let mut docs = Vec::with_capacity(losers.len() + 1);
+        if let Some(mut winner) = winner {
+            winner["_id"] = json!(id);
+            docs.push(winner);
+        }
+        for rev in losers {
+            docs.push(json!({ "_id": id, "_rev": rev, "_deleted": true }));
+        }
+
+        self.bulk_docs(docs)
+    }
+
+    /// Current revisions of the documents of `ids` which exist and aren't
+    /// deleted
+    fn current_revs(&self, ids: Vec<String>) -> Result<HashMap<String, String>, SofaError> {
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows = self.all_docs_keys(ids, &AllDocsParams::default())?.rows;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| match (row.id, row.value) {
+                (Some(id), Some(ref value)) if value.deleted != Some(true) => Some((id, value.rev.clone())),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Lists the documents of the database within the range of `params`,
+    /// along with their contents when `include_docs` is set
+    pub fn all_docs(&self, params: &AllDocsParams) -> Result<AllDocsResponse, SofaError> {
+        let response = self._client
+            .get(format!("{}/_all_docs", self.name), Some(params.to_args()))?
+            .send()?;
+
+        read_json(response)
+    }
+
+    /// Fetches the documents of a set of IDs in one request, the rows being
+    /// in the order of `keys`. Missing IDs get a row whose `error` is
+    /// `not_found`. `params` should not hold `key`, `start_key` or `end_key`.
+    pub fn all_docs_keys(&self, keys: Vec<String>, params: &AllDocsParams) -> Result<AllDocsResponse, SofaError> {
+        let response = self._client
+            .req(Method::Post, format!("{}/_all_docs", self.name), Some(params.to_args()))?
+            .body(js!(json!({ "keys": keys })))
+            .send()?;
+
+        read_json(response)
+    }
+
+    /// Fetches the changes that happened to the documents of the database, in
+    /// normal or longpoll mode
+    pub fn changes(&self, params: &ChangesParams) -> Result<Changes, SofaError> {
+        match params.feed {
+            Some(FeedMode::Continuous) => {
+                return Err(SofaError::Custom(s!("continuous feeds are read through changes_feed")))
+            }
+            Some(FeedMode::EventSource) => {
+                return Err(SofaError::Custom(s!("eventsource feeds are read through changes_eventsource")))
+            }
+            _ => {}
+        }
+
+        read_json(self.changes_response(params)?)
+    }
+
+    /// Follows the changes of the database as they happen, through a
+    /// continuous feed. See `ContinuousFeed` about timeouts and heartbeats.
+    pub fn changes_feed(&self, params: ChangesParams) -> ChangesFeed {
+        ChangesFeed::new(self.clone(), params)
+    }
+
+    /// Follows the changes of the database as they happen, through an
+    /// `eventsource` feed, see `EventSourceFeed`
+    pub fn changes_eventsource(&self, mut params: ChangesParams) -> Result<EventSourceFeed<ChangeEvent>, SofaError> {
+        params.feed = Some(FeedMode::EventSource);
+
+        Ok(EventSourceFeed::new(self.changes_response(&params)?))
+    }
+
+    /// Returns a checkpointer storing the progress of the `consumer` of the
+    /// changes of the database, see `ChangesCheckpointer`
+    pub fn checkpointer(&self, consumer: &str) -> ChangesCheckpointer {
+        ChangesCheckpointer::new(self.clone(), consumer)
+    }
+
+    pub(crate) fn changes_response(&self, params: &ChangesParams) -> Result<Response, SofaError> {
+        let path = format!("{}/_changes", self.name);
+        let response = match params.filter.as_ref().and_then(|f| f.body()) {
+            Some(body) => self._client
+                .req(Method::Post, path, Some(params.to_args()))?
+                .body(js!(body))
+                .send()?,
+            None => self._client.get(path, Some(params.to_args()))?.send()?,
+        };
+
+        ensure_success(response)
     }
 
     /// Gets all the documents in database
-    pub fn get_all(&self) -> Result<DocumentCollection, Error> {
+    pub fn get_all(&self) -> Result<DocumentCollection, SofaError> {
         self.get_all_params(None)
     }
 
     /// Gets all the documents in database, with applied parameters. Parameters description can be found here: http://docs.couchdb.org/en/latest/api/ddoc/views.html#api-ddoc-view
-    pub fn get_all_params(&self, params: Option<HashMap<String, String>>) -> Result<DocumentCollection, Error> {
+    pub fn get_all_params(&self, params: Option<HashMap<String, String>>) -> Result<DocumentCollection, SofaError> {
         let mut options;
         if let Some(opts) = params {
             options = opts;
@@ -170,75 +684,216 @@ impl Database {
             .get(self.create_document_path("_all_docs".into()), Some(options))?
             .send()?;
 
-        Ok(DocumentCollection::new(from_reader(response)?))
+        Ok(DocumentCollection::new(read_json(response)?))
+    }
+
+    /// Finds a document in the database through a Mango query, given as a
+    /// `FindQuery` or raw JSON. Parameters here http://docs.couchdb.org/en/latest/api/database/find.html
+    pub fn find<Q: Serialize>(&self, params: Q) -> Result<DocumentCollection, SofaError> {
+        find_result_documents(self.find_raw(params)?)
     }
 
-    /// Finds a document in the database through a Mango query. Parameters here http://docs.couchdb.org/en/latest/api/database/find.html
-    pub fn find(&self, params: Value) -> Result<DocumentCollection, Error> {
+    pub(crate) fn find_raw<T: DeserializeOwned, Q: Serialize>(&self, params: Q) -> Result<FindResult<T>, SofaError> {
         let path = self.create_document_path("_find".into());
         let response = self._client.post(path, js!(&params))?.send()?;
 
-        let data: FindResult = from_reader(response)?;
-        if let Some(doc_val) = data.docs {
-            let documents: Vec<Document> = doc_val
-                .into_iter()
-                .filter(|d| {
-                    // Remove _design documents
-                    let id: String = json_extr!(d["_id"]);
-                    !id.starts_with('_')
-                })
-                .map(|v| Document::new(v.clone()))
-                .collect();
+        read_json(response)
+    }
 
-            Ok(DocumentCollection::new_from_documents(documents))
-        } else if let Some(err) = data.error {
-            Err(SofaError(err).into())
-        } else {
-            Ok(DocumentCollection::default())
+    /// Finds documents through a Mango query, like `find`, deserializing them
+    /// into `T`. The result also holds the bookmark of the next page, and the
+    /// warning the server sent along, if any.
+    pub fn find_typed<T: DeserializeOwned, Q: Serialize>(&self, params: Q) -> Result<FindResult<T>, SofaError> {
+        self.find_raw(params)
+    }
+
+    /// Iterates over the documents matching a Mango query, following the
+    /// bookmarks the server returns from one page of results to the next.
+    /// Pages hold `query.limit` documents, or the server's default of 25.
+    pub fn find_paged(&self, query: FindQuery) -> FindIter {
+        FindIter::new(self.clone(), query)
+    }
+
+    /// Explains how the server would run a Mango query, given in the same
+    /// form as for `find`: which index it would use, and why
+    pub fn explain<Q: Serialize>(&self, params: Q) -> Result<ExplainResult, SofaError> {
+        let path = self.create_document_path("_explain".into());
+        let response = self._client.post(path, js!(&params))?.send()?;
+
+        read_json(response)
+    }
+
+    /// Finds the documents matching a full-text `query`, through the text
+    /// indexes of the database, see `insert_text_index`
+    pub fn find_text(&self, query: &str) -> Result<DocumentCollection, SofaError> {
+        self.find(json!({
+            "selector": { "$text": query }
+        }))
+    }
+
+    /// Returns a handle on a partition of this partitioned database, to run
+    /// queries on its documents only
+    pub fn partition(&self, partition: &str) -> Partition {
+        Partition::new(self.name.clone(), s!(partition), self._client.clone())
+    }
+
+    /// Fetches the document counts and sizes of a partition of this
+    /// partitioned database
+    pub fn partition_info(&self, partition: &str) -> Result<PartitionInfo, SofaError> {
+        self.partition(partition).info()
+    }
+
+    /// Updates a document, failing with a `SofaError::Conflict` when it was
+    /// updated in between
+    pub fn save(&self, doc: Document) -> Result<Document, SofaError> {
+        self.put_raw(doc._id.to_owned(), doc.get_data())
+    }
+
+    fn put_raw(&self, id: DocumentId, mut raw: Value) -> Result<Document, SofaError> {
+        let rev = self.put_json(id.clone(), to_string(&raw)?)?;
+        raw["_id"] = json!(id);
+        raw["_rev"] = json!(rev);
+
+        Ok(Document::new(raw))
+    }
+
+    /// Writes the JSON body of a document, returning its new revision
+    fn put_json(&self, id: DocumentId, body: String) -> Result<String, SofaError> {
+        let response = self._client
+            .put(self.create_document_path(id.clone()), body)?
+            .send()?;
+
+        if response.status() == StatusCode::Conflict {
+            return Err(conflict_error(&id, response));
+        }
+
+        let data: DocumentCreatedResult = read_json(response)?;
+
+        match (data.ok, data.rev) {
+            (Some(true), Some(rev)) => Ok(rev),
+            _ => {
+                let err = data.error.unwrap_or(s!("unspecified error"));
+                Err(SofaError::Custom(err))
+            }
         }
     }
 
-    /// Updates a document
-    pub fn save(&self, doc: Document) -> Result<Document, Error> {
-        let id = doc._id.to_owned();
-        let raw = doc.get_data();
+    /// Gets one document, deserialized into a `TypedDocument` or a struct of
+    /// your own
+    pub fn get_typed<D: CouchDocument, I: Into<DocumentId>>(&self, id: I) -> Result<D, SofaError> {
+        let response = self._client.get(self.create_document_path(id.into()), None)?.send()?;
+
+        read_json(response)
+    }
+
+    /// Saves a typed document, creating it if it doesn't exist yet, and
+    /// returns it at its new revision
+    pub fn save_typed<D: CouchDocument>(&self, mut doc: D) -> Result<D, SofaError> {
+        let rev = self.put_json(doc.id().into(), to_string(&doc)?)?;
+        doc.set_rev(rev);
+
+        Ok(doc)
+    }
+
+    /// Creates a typed document, its id being picked by the server
+    pub fn create_typed<T: Serialize>(&self, data: T) -> Result<TypedDocument<T>, SofaError> {
+        let response = self._client.post(self.name.clone(), to_string(&data)?)?.send()?;
+        let created: DocumentCreatedResult = read_json(response)?;
 
+        match (created.id, created.rev) {
+            (Some(id), Some(rev)) => Ok(TypedDocument { _id: id.into(), _rev: rev, data }),
+            _ => Err(SofaError::Custom(s!("invalid id or rev"))),
+        }
+    }
+
+    /// Removes a typed document at its current revision
+    pub fn remove_typed<D: CouchDocument>(&self, doc: &D) -> Result<String, SofaError> {
+        match doc.rev() {
+            Some(rev) => self.remove(s!(doc.id()), rev),
+            None => Err(SofaError::Custom(s!("document was never saved"))),
+        }
+    }
+
+    /// Creates a document in batch mode (`batch=ok`): the server acknowledges
+    /// it before writing it to disk, trading durability for throughput
+    pub fn create_batch(&self, raw_doc: Value) -> Result<BatchWrite, SofaError> {
+        let response = self._client.req(Method::Post, self.name.clone(), Some(batch_args()))?
+            .body(to_string(&raw_doc)?)
+            .send()?;
+
+        batch_write_result(response)
+    }
+
+    /// Updates a document in batch mode, see `create_batch`. Conflicts are
+    /// only detected when the document is stored right away.
+    pub fn save_batch(&self, doc: Document) -> Result<BatchWrite, SofaError> {
         let response = self._client
-            .put(self.create_document_path(id), to_string(&raw)?)?
+            .req(Method::Put, self.create_document_path(doc._id.clone()), Some(batch_args()))?
+            .body(to_string(&doc.get_data())?)
             .send()?;
 
-        let data: DocumentCreatedResult = from_reader(response)?;
+        batch_write_result(response)
+    }
 
-        match data.ok {
-            Some(true) => {
-                let mut val = doc.get_data();
-                val["_rev"] = json!(data.rev);
+    /// Creates or updates the document `id` from its current contents, `None`
+    /// when it doesn't exist yet, as returned by `update`. The document is
+    /// fetched and updated again when it was modified in between, up to 5
+    /// times, see `upsert_retries`.
+    pub fn upsert<F>(&self, id: &str, update: F) -> Result<Document, SofaError>
+    where
+        F: FnMut(Option<Value>) -> Value,
+    {
+        self.upsert_retries(id, 5, update)
+    }
 
-                Ok(Document::new(val))
+    /// Creates or updates a document like `upsert`, retrying at most
+    /// `max_retries` times on conflicts before giving up with a
+    /// `SofaError::Conflict`
+    pub fn upsert_retries<F>(&self, id: &str, max_retries: u32, mut update: F) -> Result<Document, SofaError>
+    where
+        F: FnMut(Option<Value>) -> Value,
+    {
+        let mut retries = 0;
+
+        loop {
+            let response = self._client.get(self.create_document_path(id.into()), None)?.send()?;
+            let current: Option<Value> = match response.status() {
+                StatusCode::NotFound => None,
+                _ => Some(read_json(response)?),
+            };
+            let rev = current.as_ref().and_then(|doc| doc["_rev"].as_str().map(String::from));
+
+            let mut updated = update(current);
+            if let Some(fields) = updated.as_object_mut() {
+                match rev {
+                    Some(rev) => fields.insert(s!("_rev"), json!(rev)),
+                    None => fields.remove("_rev"),
+                };
             }
-            Some(false) | _ => {
-                let err = data.error.unwrap_or(s!("unspecified error"));
-                return Err(SofaError(err).into());
+
+            match self.put_raw(id.into(), updated) {
+                Err(ref e) if e.is_conflict() && retries < max_retries => retries += 1,
+                result => return result,
             }
         }
     }
 
     /// Creates a document from a raw JSON document Value.
-    pub fn create(&self, raw_doc: Value) -> Result<Document, Error> {
+    pub fn create(&self, raw_doc: Value) -> Result<Document, SofaError> {
         let response = self._client.post(self.name.clone(), to_string(&raw_doc)?)?.send()?;
 
-        let data: DocumentCreatedResult = from_reader(response)?;
+        let data: DocumentCreatedResult = read_json(response)?;
 
         match data.ok {
             Some(true) => {
                 let data_id = match data.id {
                     Some(id) => id,
-                    _ => return Err(SofaError(s!("invalid id")).into()),
+                    _ => return Err(SofaError::Custom(s!("invalid id"))),
                 };
 
                 let data_rev = match data.rev {
                     Some(rev) => rev,
-                    _ => return Err(SofaError(s!("invalid rev")).into()),
+                    _ => return Err(SofaError::Custom(s!("invalid rev"))),
                 };
 
                 let mut val = raw_doc.clone();
@@ -247,74 +902,624 @@ impl Database {
 
                 Ok(Document::new(val))
             }
-            Some(false) | _ => {
+            _ => {
                 let err = data.error.unwrap_or(s!("unspecified error"));
-                return Err(SofaError(err).into());
+                Err(SofaError::Custom(err))
             }
         }
     }
 
-    /// Removes a document from the database. Returns success in a `bool`
-    pub fn remove(&self, doc: Document) -> bool {
-        let request = self._client.delete(
-            self.create_document_path(doc._id.clone()),
-            Some({
-                let mut h = HashMap::new();
-                h.insert(s!("rev"), doc._rev.clone());
-                h
-            }),
-        );
+    /// Copies a document to `dest_id` on the server side, through the `COPY`
+    /// method. Overwriting an existing document requires its current
+    /// revision, `dest_rev`.
+    pub fn copy<I: Into<DocumentId>>(&self, src_id: I, dest_id: &str, dest_rev: Option<&str>) -> Result<DocumentCreatedResult, SofaError> {
+        let mut destination = encode_doc_id(dest_id);
+        if let Some(rev) = dest_rev {
+            destination.push_str("?rev=");
+            destination.push_str(&encode_path_segment(rev));
+        }
 
-        request
-            .and_then(|mut req| {
-                Ok(req.send()
-                    .and_then(|res| {
-                        Ok(match res.status() {
-                            StatusCode::Ok | StatusCode::Accepted => true,
-                            _ => false,
-                        })
-                    })
-                    .unwrap_or(false))
+        let mut headers = Headers::new();
+        headers.set_raw("Destination", destination);
+
+        let response = self._client
+            .req(Method::Extension(s!("COPY")), self.create_document_path(src_id.into()), None)?
+            .headers(headers)
+            .send()?;
+
+        read_json(response)
+    }
+
+    /// Removes a document from the database at its revision `rev`, returning
+    /// the revision of the deletion. Fails with a `SofaError::Conflict` when `rev`
+    /// isn't the current revision of the document.
+    pub fn remove<I: Into<DocumentId>>(&self, id: I, rev: &str) -> Result<String, SofaError> {
+        let id: DocumentId = id.into();
+
+        let mut args = HashMap::new();
+        args.insert(s!("rev"), s!(rev));
+
+        let response = self._client.delete(self.create_document_path(id.clone()), Some(args))?.send()?;
+        if response.status() == StatusCode::Conflict {
+            return Err(conflict_error(&id, response));
+        }
+
+        let data: DocumentCreatedResult = read_json(response)?;
+        data.rev.ok_or_else(|| SofaError::Custom(s!("invalid rev")))
+    }
+
+    /// Removes a document from the database at the revision it was read at,
+    /// see `remove`
+    pub fn remove_doc(&self, doc: &Document) -> Result<String, SofaError> {
+        self.remove(doc._id.clone(), &doc._rev)
+    }
+
+    fn create_attachment_path(&self, doc_id: &str, name: &str) -> String {
+        format!("{}/{}/{}", self.name, encode_doc_id(doc_id), encode_path_segment(name))
+    }
+
+    /// Uploads an attachment of the document `doc_id` at its revision `rev`,
+    /// creating the document when it doesn't exist and no revision is given.
+    /// Returns the new revision of the document, failing with a
+    /// `SofaError::Conflict` when `rev` isn't its current revision.
+    pub fn put_attachment<B: Into<AttachmentBody>>(
+        &self,
+        doc_id: &str,
+        rev: Option<&str>,
+        name: &str,
+        content_type: &str,
+        body: B,
+    ) -> Result<String, SofaError> {
+        let args = rev.map(|rev| {
+            let mut args = HashMap::new();
+            args.insert(s!("rev"), s!(rev));
+            args
+        });
+
+        let mut headers = Headers::new();
+        headers.set_raw("Content-Type", s!(content_type));
+
+        let response = self._client
+            .req(Method::Put, self.create_attachment_path(doc_id, name), args)?
+            .headers(headers)
+            .send_body(body.into().into_body())?;
+
+        if response.status() == StatusCode::Conflict {
+            return Err(conflict_error(doc_id, response));
+        }
+
+        let data: DocumentCreatedResult = read_json(response)?;
+        data.rev.ok_or_else(|| SofaError::Custom(s!("invalid rev")))
+    }
+
+    /// Downloads an attachment of a document, returning a reader streaming
+    /// its contents, so that large attachments aren't held in memory
+    pub fn get_attachment(&self, doc_id: &str, name: &str) -> Result<AttachmentReader, SofaError> {
+        let response = self._client.get(self.create_attachment_path(doc_id, name), None)?.send()?;
+
+        Ok(AttachmentReader::new(ensure_success(response)?))
+    }
+
+    /// Deletes an attachment of the document `doc_id` at its revision `rev`,
+    /// returning the new revision of the document. Fails with a
+    /// `SofaError::Conflict` when `rev` isn't its current revision.
+    pub fn delete_attachment(&self, doc_id: &str, rev: &str, name: &str) -> Result<String, SofaError> {
+        let mut args = HashMap::new();
+        args.insert(s!("rev"), s!(rev));
+
+        let response = self._client.delete(self.create_attachment_path(doc_id, name), Some(args))?.send()?;
+        if response.status() == StatusCode::Conflict {
+            return Err(conflict_error(doc_id, response));
+        }
+
+        let data: DocumentCreatedResult = read_json(response)?;
+        data.rev.ok_or_else(|| SofaError::Custom(s!("invalid rev")))
+    }
+
+    /// Fetches a document along with the contents of all its attachments, in
+    /// a single `multipart/related` response
+    pub fn get_with_attachments<I: Into<DocumentId>>(&self, id: I) -> Result<MultipartDocument, SofaError> {
+        let mut args = HashMap::new();
+        args.insert(s!("attachments"), s!("true"));
+
+        let mut headers = Headers::new();
+        headers.set_raw("Accept", "multipart/related");
+
+        let response = self._client.get(self.create_document_path(id.into()), Some(args))?.headers(headers).send()?;
+        let mut response = ensure_success(response)?;
+
+        let boundary = response.headers().get::<ContentType>().and_then(|ct| multipart_boundary(&ct.to_string()));
+        let boundary = match boundary {
+            Some(boundary) => boundary,
+            // Documents without attachments are sent as plain JSON
+            None => {
+                return Ok(MultipartDocument {
+                    doc: Document::new(response.json()?),
+                    attachments: vec![],
+                })
+            }
+        };
+
+        let mut body = Vec::new();
+        response.read_to_end(&mut body)?;
+
+        let mut parts = parse_multipart(&boundary, &body)?.into_iter();
+        let doc: Value = match parts.next() {
+            Some(part) => serde_json::from_slice(&part.body)?,
+            None => return Err(SofaError::Custom(s!("empty multipart body"))),
+        };
+
+        let mut names = doc["_attachments"]
+            .as_object()
+            .map(|attachments| attachments.keys().cloned().collect::<Vec<String>>())
+            .unwrap_or_default()
+            .into_iter();
+        let attachments = parts
+            .map(|part| {
+                let name = part
+                    .header("Content-Disposition")
+                    .and_then(disposition_filename)
+                    .or_else(|| names.next())
+                    .unwrap_or_default();
+                let content_type = part
+                    .header("Content-Type")
+                    .map(String::from)
+                    .or_else(|| doc["_attachments"][&name]["content_type"].as_str().map(String::from))
+                    .unwrap_or_default();
+
+                AttachmentPart {
+                    name,
+                    content_type,
+                    data: part.body,
+                }
             })
-            .unwrap_or(false)
+            .collect();
+
+        Ok(MultipartDocument {
+            doc: Document::new(doc),
+            attachments,
+        })
+    }
+
+    /// Saves a document along with new attachments in a single
+    /// `multipart/related` request, so that they are all committed under one
+    /// new revision. Attachments already stored with the document are kept,
+    /// and the last of several attachments given the same name wins.
+    pub fn save_with_attachments(&self, doc: Document, attachments: Vec<AttachmentPart>) -> Result<Document, SofaError> {
+        let id = doc._id.to_owned();
+        let mut raw = doc.get_data();
+
+        let mut given: HashMap<String, AttachmentPart> = attachments.into_iter()
+            .map(|attachment| (attachment.name.clone(), attachment))
+            .collect();
+
+        if !raw["_attachments"].is_object() {
+            raw["_attachments"] = json!({});
+        }
+        for attachment in given.values() {
+            raw["_attachments"][&attachment.name] = json!({
+                "follows": true,
+                "content_type": attachment.content_type,
+                "length": attachment.data.len()
+            });
+        }
+
+        // CouchDB matches the parts with the attachments to follow in the
+        // order they are serialized in, that of the `_attachments` object
+        let attachments: Vec<AttachmentPart> = raw["_attachments"].as_object()
+            .into_iter()
+            .flat_map(|stubs| stubs.iter())
+            .filter(|&(_, stub)| stub["follows"] == json!(true))
+            .filter_map(|(name, _)| given.remove(name))
+            .collect();
+
+        let json = to_string(&raw)?;
+        let parts: Vec<&[u8]> = attachments.iter().map(|a| a.data.as_slice()).chain(Some(json.as_bytes())).collect();
+        let boundary = pick_boundary(&parts);
+        let body = write_multipart(&boundary, json.as_bytes(), &attachments);
+
+        let mut headers = Headers::new();
+        headers.set_raw("Content-Type", format!("multipart/related; boundary=\"{}\"", boundary));
+
+        let response = self._client.req(Method::Put, self.create_document_path(id.clone()), None)?
+            .headers(headers)
+            .body(body)
+            .send()?;
+
+        if response.status() == StatusCode::Conflict {
+            return Err(conflict_error(&id, response));
+        }
+
+        let data: DocumentCreatedResult = read_json(response)?;
+
+        for attachment in &attachments {
+            raw["_attachments"][&attachment.name] = json!({
+                "content_type": attachment.content_type,
+                "length": attachment.data.len(),
+                "stub": true
+            });
+        }
+        raw["_id"] = json!(id);
+        raw["_rev"] = json!(data.rev);
+
+        Ok(Document::new(raw))
+    }
+
+    fn create_local_path(&self, id: &str) -> String {
+        let id = id.strip_prefix(LOCAL_ID_PREFIX).unwrap_or(id);
+
+        format!("{}/{}{}", self.name, LOCAL_ID_PREFIX, encode_path_segment(id))
+    }
+
+    /// Gets a local document, whose ID can be given with or without its
+    /// `_local/` prefix
+    pub fn get_local(&self, id: &str) -> Result<Document, SofaError> {
+        let response = self._client.get(self.create_local_path(id), None)?.send()?;
+
+        Ok(Document::new(read_json(response)?))
+    }
+
+    /// Creates or updates a local document. Local documents are never
+    /// replicated, making them suited to replication checkpoints and
+    /// client-side state. Updates must carry the current `_rev`.
+    pub fn save_local(&self, id: &str, raw_doc: Value) -> Result<Document, SofaError> {
+        let response = self._client.put(self.create_local_path(id), to_string(&raw_doc)?)?.send()?;
+        let data: DocumentCreatedResult = read_json(response)?;
+
+        let mut val = raw_doc;
+        val["_id"] = json!(data.id);
+        val["_rev"] = json!(data.rev);
+
+        Ok(Document::new(val))
+    }
+
+    /// Removes a local document at its current revision
+    pub fn remove_local(&self, id: &str, rev: &str) -> Result<bool, SofaError> {
+        let mut args = HashMap::new();
+        args.insert(s!("rev"), s!(rev));
+
+        let response = self._client.delete(self.create_local_path(id), Some(args))?.send()?;
+        let s: CouchResponse = read_json(response)?;
+
+        Ok(s.ok.unwrap_or(false))
+    }
+
+    /// Lists the local documents of the database, with applied parameters such as `include_docs`. Parameters description can be found here: http://docs.couchdb.org/en/latest/api/local.html
+    pub fn get_local_docs(&self, params: Option<HashMap<String, String>>) -> Result<AllDocsResponse, SofaError> {
+        let response = self._client.get(format!("{}/_local_docs", self.name), params)?.send()?;
+
+        read_json(response)
+    }
+
+    /// Lists the design documents of the database, along with their
+    /// contents when `include_docs` is set
+    pub fn list_design_docs(&self, include_docs: bool) -> Result<AllDocsResponse, SofaError> {
+        let mut args = HashMap::new();
+        args.insert(s!("include_docs"), include_docs.to_string());
+
+        let response = self._client.get(format!("{}/_design_docs", self.name), Some(args))?.send()?;
+
+        read_json(response)
+    }
+
+    /// Gets the design document `name`, given with or without its `_design/`
+    /// prefix
+    pub fn get_design(&self, name: &str) -> Result<DesignDocument, SofaError> {
+        self.get_typed(design_id(name))
+    }
+
+    /// Saves a design document, creating it if it doesn't exist yet, and
+    /// returns it at its new revision
+    pub fn save_design(&self, ddoc: DesignDocument) -> Result<DesignDocument, SofaError> {
+        self.save_typed(ddoc)
+    }
+
+    /// Deletes the design document `name` at its revision `rev`, returning
+    /// the revision of the deletion
+    pub fn delete_design(&self, name: &str, rev: &str) -> Result<String, SofaError> {
+        self.remove(design_id(name), rev)
+    }
+
+    /// Path of the function `name` of the design document `ddoc`, `kind`
+    /// being `_view`, `_update`, `_show`, `_list` or `_search`
+    fn create_design_function_path(&self, ddoc: &str, kind: &str, name: &str) -> String {
+        format!(
+            "{}/{}{}/{}/{}",
+            self.name,
+            DESIGN_ID_PREFIX,
+            encode_design_name(ddoc),
+            kind,
+            encode_path_segment(name)
+        )
+    }
+
+    /// Queries the view `view` of the design document `ddoc`, given with or
+    /// without its `_design/` prefix, deserializing the keys and values of
+    /// its rows. Queries with `keys` are sent as a `POST`. Parameters
+    /// description can be found here: http://docs.couchdb.org/en/latest/api/ddoc/views.html#api-ddoc-view
+    pub fn query_view<K: DeserializeOwned, V: DeserializeOwned>(
+        &self,
+        ddoc: &str,
+        view: &str,
+        params: &ViewQuery,
+    ) -> Result<ViewResponse<K, V>, SofaError> {
+        Ok(self.query_view_with_meta(ddoc, view, params)?.into_inner())
+    }
+
+    /// Queries a view like `query_view`, along with the metadata of the
+    /// response, whose `ETag` changes with the result
+    pub fn query_view_with_meta<K: DeserializeOwned, V: DeserializeOwned>(
+        &self,
+        ddoc: &str,
+        view: &str,
+        params: &ViewQuery,
+    ) -> Result<WithMeta<ViewResponse<K, V>>, SofaError> {
+        let path = self.create_design_function_path(ddoc, "_view", view);
+        let response = match params.keys {
+            Some(ref keys) => self._client
+                .req(Method::Post, path, Some(params.to_args()))?
+                .body(js!(json!({ "keys": keys })))
+                .send()?,
+            None => self._client.get(path, Some(params.to_args()))?.send()?,
+        };
+
+        read_json_with_meta(response)
+    }
+
+    /// Runs several queries against the same view in one request, returning
+    /// one result set per query, in order
+    pub fn query_view_multi<K: DeserializeOwned, V: DeserializeOwned>(
+        &self,
+        ddoc: &str,
+        view: &str,
+        queries: &[ViewQuery],
+    ) -> Result<Vec<ViewResponse<K, V>>, SofaError> {
+        let path = format!("{}/queries", self.create_design_function_path(ddoc, "_view", view));
+        let response = self._client
+            .post(path, js!(json!({ "queries": queries })))?
+            .send()?;
+        let data: ViewQueriesResponse<K, V> = read_json(response)?;
+
+        Ok(data.results)
+    }
+
+    /// Builds the view indexes of the design document `ddoc`, which its
+    /// views share, so that querying them doesn't wait for the build later
+    /// on. Without `wait`, one of the views is queried with `update=lazy`,
+    /// which triggers the build in the background and returns right away.
+    /// With `wait`, the query waits for the indexes to be up to date, erroring
+    /// out if that takes longer than `wait`.
+    pub fn warm_views(&self, ddoc: &str, wait: Option<Duration>) -> Result<(), SofaError> {
+        let design = self.get_design(ddoc)?;
+        let view = match design.views.keys().next() {
+            Some(view) => view,
+            None => return Ok(()),
+        };
+
+        match wait {
+            Some(wait) => {
+                let query = ViewQuery::new().limit(0);
+                self.with_timeout(wait)?.query_view::<Value, Value>(ddoc, view, &query)?;
+            }
+            None => {
+                let query = ViewQuery::new().limit(0).update(ViewUpdate::Lazy);
+                self.query_view::<Value, Value>(ddoc, view, &query)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Iterates over the rows of a view, fetching `page_size` rows at a time
+    /// and starting each page after the last row of the previous one. The
+    /// iteration stops after `query.limit` rows, if set.
+    pub fn query_view_paged<K: DeserializeOwned, V: DeserializeOwned>(
+        &self,
+        ddoc: &str,
+        view: &str,
+        query: ViewQuery,
+        page_size: u64,
+    ) -> ViewIter<K, V> {
+        ViewIter::new(self.clone(), ddoc, view, query, page_size)
+    }
+
+    /// Runs the update handler `handler` of the design document `ddoc`, on
+    /// the document `doc_id`, or without a document when it is `None`. `body`
+    /// is sent as JSON to the handler, which decides what to save.
+    pub fn execute_update(
+        &self,
+        ddoc: &str,
+        handler: &str,
+        doc_id: Option<&str>,
+        body: Option<Value>,
+    ) -> Result<UpdateResponse, SofaError> {
+        let mut path = self.create_design_function_path(ddoc, "_update", handler);
+        let method = match doc_id {
+            Some(id) => {
+                path.push('/');
+                path.push_str(&encode_doc_id(id));
+                Method::Put
+            }
+            None => Method::Post,
+        };
+
+        let mut request = self._client.req(method, path, None)?;
+        if let Some(ref body) = body {
+            request.body(to_string(body)?);
+        }
+        let mut response = ensure_success(request.send()?)?;
+
+        Ok(UpdateResponse {
+            new_rev: raw_header(&response, "X-Couch-Update-NewRev"),
+            id: raw_header(&response, "X-Couch-Id"),
+            content_type: response.headers().get::<ContentType>().map(|ct| ct.to_string()),
+            body: response.text()?,
+        })
+    }
+
+    /// Renders the document `doc_id` through the show function `show` of the
+    /// design document `ddoc`, or renders nothing through it when `doc_id`
+    /// is `None`. `params` are passed to the function as is.
+    pub fn execute_show(
+        &self,
+        ddoc: &str,
+        show: &str,
+        doc_id: Option<&str>,
+        params: Option<HashMap<String, String>>,
+    ) -> Result<RenderedResponse, SofaError> {
+        let mut path = self.create_design_function_path(ddoc, "_show", show);
+        if let Some(id) = doc_id {
+            path.push('/');
+            path.push_str(&encode_doc_id(id));
+        }
+
+        let response = self._client.get(path, params)?.send()?;
+        read_rendered(response)
+    }
+
+    /// Renders the rows of the view `view` through the list function `list`
+    /// of the design document `ddoc`. `view` can be given as `{ddoc}/{view}`
+    /// to use a view of another design document. `params` are passed to the
+    /// function and the view as is.
+    pub fn execute_list(
+        &self,
+        ddoc: &str,
+        list: &str,
+        view: &str,
+        params: Option<HashMap<String, String>>,
+    ) -> Result<RenderedResponse, SofaError> {
+        let mut path = self.create_design_function_path(ddoc, "_list", list);
+        for segment in view.splitn(2, '/') {
+            path.push('/');
+            path.push_str(&encode_path_segment(segment));
+        }
+
+        let response = self._client.get(path, params)?.send()?;
+        read_rendered(response)
+    }
+
+    /// Runs a full-text search query against the search index `index` of
+    /// the design document `ddoc`, deserializing the stored fields of the
+    /// rows. Requires a server running full-text search. Parameters
+    /// description can be found here: https://docs.couchdb.org/en/latest/ddocs/search.html#queries
+    pub fn search<F: DeserializeOwned>(
+        &self,
+        ddoc: &str,
+        index: &str,
+        query: &SearchQuery,
+    ) -> Result<SearchResponse<F>, SofaError> {
+        let response = self._client
+            .post(self.create_design_function_path(ddoc, "_search", index), js!(query))?
+            .send()?;
+
+        read_json(response)
     }
 
     /// Inserts an index in a naive way, if it already exists, will throw an
     /// `Err`
-    pub fn insert_index(&self, name: String, spec: IndexFields) -> Result<IndexCreated, Error> {
+    pub fn insert_index(&self, name: String, spec: IndexFields) -> Result<IndexCreated, SofaError> {
+        self.post_index(json!({
+            "name": name,
+            "index": spec
+        }))
+    }
+
+    /// Inserts a text index, to run `$text` queries against, see
+    /// `find_text`. Requires a server running full-text search.
+    pub fn insert_text_index(&self, name: String, spec: TextIndexFields) -> Result<IndexCreated, SofaError> {
+        self.post_index(json!({
+            "name": name,
+            "type": "text",
+            "index": spec
+        }))
+    }
+
+    fn post_index(&self, body: Value) -> Result<IndexCreated, SofaError> {
         let response = self._client
-            .post(
-                self.create_document_path("_index".into()),
-                js!(json!({
-                "name": name,
-                "index": spec
-            })),
-            )?
+            .post(self.create_document_path("_index".into()), js!(body))?
             .send()?;
 
-        let data: IndexCreated = from_reader(response)?;
+        let data: IndexCreated = read_json(response)?;
 
         if data.error.is_some() {
             let err = data.error.unwrap_or(s!("unspecified error"));
-            Err(SofaError(err).into())
+            Err(SofaError::Custom(err))
         } else {
             Ok(data)
         }
     }
 
     /// Reads the database's indexes and returns them
-    pub fn read_indexes(&self) -> Result<DatabaseIndexList, Error> {
+    pub fn read_indexes(&self) -> Result<DatabaseIndexList, SofaError> {
         let response = self._client
             .get(self.create_document_path("_index".into()), None)?
             .send()?;
 
-        Ok(from_reader(response)?)
+        read_json(response)
+    }
+
+    /// Deletes the JSON index `name` of the design document `ddoc`, which can
+    /// be given with or without its `_design/` prefix
+    pub fn delete_index(&self, ddoc: &str, name: &str) -> Result<bool, SofaError> {
+        self.delete_index_of_type(ddoc, "json", name)
+    }
+
+    /// Deletes the text index `name` of the design document `ddoc`
+    pub fn delete_text_index(&self, ddoc: &str, name: &str) -> Result<bool, SofaError> {
+        self.delete_index_of_type(ddoc, "text", name)
+    }
+
+    fn delete_index_of_type(&self, ddoc: &str, index_type: &str, name: &str) -> Result<bool, SofaError> {
+        let path = format!(
+            "{}/_index/{}/{}/{}",
+            self.name,
+            encode_design_name(ddoc),
+            index_type,
+            encode_path_segment(name)
+        );
+
+        let response = self._client.delete(path, None)?.send()?;
+        let s: CouchResponse = read_json(response)?;
+
+        Ok(s.ok.unwrap_or(false))
+    }
+
+    /// Ensures an index able to answer `query` exists, see
+    /// `FindQuery::index_fields`, creating it when none of the JSON indexes
+    /// of the database starts with the fields the query needs. Returns `true`
+    /// when an index was created.
+    pub fn ensure_index_for(&self, query: &FindQuery) -> Result<bool, SofaError> {
+        let fields = query.index_fields();
+        if fields.is_empty() {
+            return Ok(false);
+        }
+
+        let covered = self.read_indexes()?.indexes.iter().any(|index| {
+            let indexed: Vec<&str> = index.def.fields.iter().filter_map(|field| match *field {
+                SortSpec::Simple(ref field) => Some(field.as_str()),
+                SortSpec::Complex(ref spec) => spec.keys().next().map(String::as_str),
+            }).collect();
+
+            index.index_type == "json"
+                && index.def.partial_filter_selector.is_none()
+                && indexed.len() >= fields.len()
+                && indexed.iter().zip(&fields).all(|(indexed, field)| indexed == field)
+        });
+        if covered {
+            return Ok(false);
+        }
+
+        let name = format!("auto-{}", fields.join("-"));
+        let spec = IndexFields::new(fields.into_iter().map(SortSpec::Simple).collect());
+        self.insert_index(name, spec)?;
+
+        Ok(true)
     }
 
     /// Method to ensure an index is created on the database with the following
     /// spec. Returns `true` when we created a new one, or `false` when the
     /// index was already existing.
-    pub fn ensure_index(&self, name: String, spec: IndexFields) -> Result<bool, Error> {
+    pub fn ensure_index(&self, name: String, spec: IndexFields) -> Result<bool, SofaError> {
         let db_indexes = self.read_indexes()?;
 
         // We look for our index
@@ -332,3 +1537,69 @@ impl Database {
         Ok(true)
     }
 }
+
+fn batch_args() -> HashMap<String, String> {
+    let mut args = HashMap::new();
+    args.insert(s!("batch"), s!("ok"));
+    args
+}
+
+fn batch_write_result(response: Response) -> Result<BatchWrite, SofaError> {
+    let accepted = response.status() == StatusCode::Accepted;
+    let data: DocumentCreatedResult = read_json(response)?;
+    let id = data.id.ok_or_else(|| SofaError::Custom(s!("invalid id")))?;
+
+    match data.rev {
+        Some(rev) if !accepted => Ok(BatchWrite::Stored { id, rev }),
+        _ => Ok(BatchWrite::Accepted { id }),
+    }
+}
+
+/// Checks if the database of an active task is `name`, tasks of clustered
+/// servers referring to a shard such as `shards/00000000-7fffffff/name.1530000000`
+fn task_database_is(task_db: &str, name: &str) -> bool {
+    if task_db == name {
+        return true;
+    }
+
+    task_db
+        .strip_prefix("shards/")
+        .and_then(|shard| shard.split_once('/').map(|(_, shard)| shard))
+        .and_then(|shard| shard.rsplit_once('.').map(|(db, _)| db))
+        .is_some_and(|db| db == name)
+}
+
+/// Collects the documents found by a Mango query, design documents excluded
+pub(crate) fn find_result_documents(data: FindResult) -> Result<DocumentCollection, SofaError> {
+    #[cfg(feature = "log")]
+    {
+        if let Some(ref warning) = data.warning {
+            warn!("_find: {}", warning);
+        }
+    }
+
+    if let Some(doc_val) = data.docs {
+        let documents = doc_val
+            .into_iter()
+            // Remove _design documents
+            .filter(|d| !d["_id"].as_str().unwrap_or_default().starts_with('_'))
+            .map(Document::try_new)
+            .collect::<Result<Vec<Document>, SofaError>>()?;
+
+        Ok(DocumentCollection::new_from_documents(documents))
+    } else if let Some(err) = data.error {
+        Err(SofaError::Custom(err))
+    } else {
+        Ok(DocumentCollection::default())
+    }
+}
+
+/// Reads the content type and body of a show or list function response
+fn read_rendered(response: Response) -> Result<RenderedResponse, SofaError> {
+    let mut response = ensure_success(response)?;
+
+    Ok(RenderedResponse {
+        content_type: response.headers().get::<ContentType>().map(|ct| ct.to_string()),
+        body: response.text()?,
+    })
+}
@@ -0,0 +1,107 @@
+use database::*;
+use error::SofaError;
+use types::*;
+
+/// What `DesignSync` did, or would do, with a design document
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum DesignSyncAction {
+    /// The design document isn't in the database yet
+    Create,
+    /// The design document differs from the one in the database
+    Update,
+    /// The design document matches the one in the database, and is left
+    /// alone so that its view indexes aren't rebuilt
+    Unchanged,
+}
+
+/// Outcome of `DesignSync` for one design document
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct DesignSyncResult {
+    /// Full ID of the design document
+    pub id: String,
+    pub action: DesignSyncAction,
+    /// Revision of the design document in the database, once synced
+    pub rev: Option<String>,
+}
+
+/// DesignSync deploys a set of design documents defined in code, only writing
+/// the ones whose content differs from what the database holds. Updating a
+/// design document rebuilds its view indexes, which unchanged documents are
+/// spared on every deploy.
+#[derive(Debug, Clone)]
+pub struct DesignSync {
+    db: Database,
+    ddocs: Vec<DesignDocument>,
+}
+
+impl DesignSync {
+    pub fn new(db: Database) -> DesignSync {
+        DesignSync {
+            db,
+            ddocs: Vec::new(),
+        }
+    }
+
+    /// Adds a design document to the set to deploy. Its `rev` is ignored.
+    pub fn design(mut self, ddoc: DesignDocument) -> DesignSync {
+        self.ddocs.push(ddoc);
+        self
+    }
+
+    /// Compares the design documents with the ones in the database, without
+    /// writing anything
+    pub fn plan(&self) -> Result<Vec<DesignSyncResult>, SofaError> {
+        Ok(self.compare()?.into_iter().map(|(result, _)| result).collect())
+    }
+
+    /// Creates or updates the design documents that differ from the ones in
+    /// the database
+    pub fn sync(&self) -> Result<Vec<DesignSyncResult>, SofaError> {
+        self.compare()?
+            .into_iter()
+            .map(|(mut result, ddoc)| {
+                if result.action != DesignSyncAction::Unchanged {
+                    let mut ddoc = ddoc.clone();
+                    ddoc.rev = result.rev.take();
+                    result.rev = self.db.save_design(ddoc)?.rev;
+                }
+
+                Ok(result)
+            })
+            .collect()
+    }
+
+    fn compare(&self) -> Result<Vec<(DesignSyncResult, &DesignDocument)>, SofaError> {
+        let ids: Vec<&str> = self.ddocs.iter().map(|ddoc| ddoc.id.as_str()).collect();
+        let mut current = self.db.get_bulk_typed::<DesignDocument, _>(ids)?;
+
+        Ok(self.ddocs
+            .iter()
+            .map(|ddoc| {
+                let (action, rev) = match current.remove(ddoc.id.as_str()) {
+                    Some(FetchedDoc::Found(mut existing)) => {
+                        let rev = existing.rev.take();
+                        let local = DesignDocument {
+                            rev: None,
+                            ..ddoc.clone()
+                        };
+
+                        if existing == local {
+                            (DesignSyncAction::Unchanged, rev)
+                        } else {
+                            (DesignSyncAction::Update, rev)
+                        }
+                    }
+                    _ => (DesignSyncAction::Create, None),
+                };
+
+                let result = DesignSyncResult {
+                    id: ddoc.id.clone(),
+                    action,
+                    rev,
+                };
+                (result, ddoc)
+            })
+            .collect())
+    }
+}
@@ -1,9 +1,18 @@
+use std::collections::HashMap;
+
 use database::*;
+use error::SofaError;
 use serde_json;
-use serde_json::Value;
+use serde_json::{Map, Value};
 use std::ops::{Index, IndexMut};
 use types::*;
 
+impl<'a> From<&'a Document> for BulkRemoveItem {
+    fn from(doc: &'a Document) -> BulkRemoveItem {
+        BulkRemoveItem::new(&doc._id, Some(&doc._rev))
+    }
+}
+
 /// Document abstracts the handling of JSON values and provides direct access
 /// and casting to the fields of your documents You can get access to the
 /// fields via the implementation of the `Index` and `IndexMut` traits
@@ -21,14 +30,34 @@ pub struct Document {
 }
 
 impl Document {
+    /// Wraps a raw JSON document. `_id` and `_rev` are left empty when the
+    /// document lacks them, as do the results of a Mango query whose
+    /// `fields` leave them out.
     pub fn new(doc: Value) -> Document {
         Document {
-            _id: json_extr!(doc["_id"]),
-            _rev: json_extr!(doc["_rev"]),
-            doc: doc,
+            _id: doc["_id"].as_str().unwrap_or_default().into(),
+            _rev: s!(doc["_rev"].as_str().unwrap_or_default()),
+            doc,
         }
     }
 
+    /// Wraps a raw JSON document like `new`, failing when it isn't an object
+    /// or when its `_id` or `_rev` aren't strings
+    pub fn try_new(doc: Value) -> Result<Document, SofaError> {
+        if !doc.is_object() {
+            return Err(SofaError::Custom(format!("document is not an object: {}", doc)));
+        }
+
+        for field in &["_id", "_rev"] {
+            match doc.get(*field) {
+                None | Some(&Value::String(_)) => {}
+                Some(value) => return Err(SofaError::Custom(format!("{} is not a string: {}", field, value))),
+            }
+        }
+
+        Ok(Document::new(doc))
+    }
+
     /// Returns all document's keys
     pub fn get_keys(&self) -> Vec<String> {
         let mut ret: Vec<String> = Vec::new();
@@ -42,6 +71,34 @@ impl Document {
         ret
     }
 
+    /// Parses the revision of the document
+    pub fn rev(&self) -> Result<Rev, SofaError> {
+        Rev::parse(&self._rev)
+    }
+
+    /// Parses the revision metadata the document was fetched with, see
+    /// `GetDocParams`
+    pub fn revisions(&self) -> Result<DocumentRevisions, SofaError> {
+        let mut revisions = Map::new();
+
+        for field in &["_revisions", "_revs_info", "_conflicts", "_deleted_conflicts"] {
+            if let Some(value) = self.doc.get(*field) {
+                revisions.insert(s!(*field), value.clone());
+            }
+        }
+
+        Ok(serde_json::from_value(Value::Object(revisions))?)
+    }
+
+    /// Returns the attachments of the document, by name: stubs unless the
+    /// document was fetched with `GetDocParams::attachments`
+    pub fn attachments(&self) -> HashMap<String, Attachment> {
+        self.doc
+            .get("_attachments")
+            .and_then(|attachments| serde_json::from_value(attachments.clone()).ok())
+            .unwrap_or_default()
+    }
+
     /// Returns raw JSON data from document
     pub fn get_data(&self) -> Value {
         self.doc.clone()
@@ -69,18 +126,18 @@ impl Document {
     /// Recursively populates field (must be an array of IDs from another
     /// database) with provided database documents
     pub fn populate(&mut self, field: &String, db: Database) -> &Self {
-        let ref val = self[field].clone();
+        let val = &self[field].clone();
         if *val == Value::Null {
             return self;
         }
 
         let ids = val.as_array()
             .unwrap_or(&Vec::new())
-            .into_iter()
-            .map(|v| s!(v.as_str().unwrap_or("")))
+            .iter()
+            .map(|v| DocumentId::from(v.as_str().unwrap_or("")))
             .collect();
 
-        let data = db.get_bulk(ids).and_then(|docs| Ok(docs.get_data()));
+        let data = db.get_bulk(ids).map(|docs| docs.get_data());
 
         match data {
             Ok(data) => {
@@ -139,7 +196,7 @@ pub struct DocumentCollectionItem {
 impl DocumentCollectionItem {
     pub fn new(doc: Document) -> DocumentCollectionItem {
         let id = doc._id.clone();
-        DocumentCollectionItem { doc: doc, id: id }
+        DocumentCollectionItem { doc, id }
     }
 }
 
@@ -181,7 +238,7 @@ impl DocumentCollection {
         DocumentCollection {
             offset: 0,
             total_rows: len,
-            rows: docs.into_iter().map(|d| DocumentCollectionItem::new(d)).collect(),
+            rows: docs.into_iter().map(DocumentCollectionItem::new).collect(),
         }
     }
 
@@ -195,7 +252,7 @@ impl Index<usize> for DocumentCollection {
     type Output = DocumentCollectionItem;
 
     fn index(&self, index: usize) -> &DocumentCollectionItem {
-        &self.rows.get(index).unwrap()
+        self.rows.get(index).unwrap()
     }
 }
 
@@ -1,3 +1,253 @@
-#[derive(Fail, Debug)]
-#[fail(display = "Custom error: {}", _0)]
-pub struct SofaError(pub String);
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+use std::result;
+
+use reqwest::{self, StatusCode};
+use serde_json;
+use url;
+
+use types::ResponseMeta;
+
+/// Result of the operations of sofa
+pub type Result<T, E = SofaError> = result::Result<T, E>;
+
+/// Errors of sofa. Unsuccessful responses of the server are mapped to a
+/// variant by their HTTP status, along with the `error` and `reason` of the
+/// error body CouchDB sent, and the `meta` of the response, whose request ID
+/// the server logged the failure under.
+#[derive(Debug)]
+pub enum SofaError {
+    /// 404: the database, document or other resource doesn't exist
+    NotFound {
+        error: String,
+        reason: String,
+        meta: Box<ResponseMeta>,
+    },
+    /// 409: the write was based on a revision that isn't the current one
+    /// anymore. `id` is the ID of the document, when known.
+    Conflict {
+        id: Option<String>,
+        error: String,
+        reason: String,
+        meta: Box<ResponseMeta>,
+    },
+    /// 401: the credentials are missing or invalid
+    Unauthorized {
+        error: String,
+        reason: String,
+        meta: Box<ResponseMeta>,
+    },
+    /// 403: the user isn't allowed to make the request
+    Forbidden {
+        error: String,
+        reason: String,
+        meta: Box<ResponseMeta>,
+    },
+    /// 412: the database already exists, or a header condition failed
+    PreconditionFailed {
+        error: String,
+        reason: String,
+        meta: Box<ResponseMeta>,
+    },
+    /// 429: the server is rate limiting requests
+    TooManyRequests {
+        error: String,
+        reason: String,
+        meta: Box<ResponseMeta>,
+    },
+    /// Any other unsuccessful response
+    ServerError {
+        status: u16,
+        error: String,
+        reason: String,
+        meta: Box<ResponseMeta>,
+    },
+    /// The request couldn't be sent, or its response couldn't be read
+    Transport(Box<dyn StdError + Send + Sync>),
+    /// A body couldn't be serialized or deserialized
+    Serialization(Box<dyn StdError + Send + Sync>),
+    /// Any other error, such as an invalid argument or an unexpected
+    /// response
+    Custom(String),
+}
+
+impl SofaError {
+    /// Maps an unsuccessful status, and the `error` and `reason` of the body
+    /// sent along, to an error
+    pub fn from_status(status: StatusCode, error: String, reason: String) -> SofaError {
+        let meta = Box::default();
+
+        match status {
+            StatusCode::NotFound => SofaError::NotFound { error, reason, meta },
+            StatusCode::Conflict => SofaError::Conflict {
+                id: None,
+                error,
+                reason,
+                meta,
+            },
+            StatusCode::Unauthorized => SofaError::Unauthorized { error, reason, meta },
+            StatusCode::Forbidden => SofaError::Forbidden { error, reason, meta },
+            StatusCode::PreconditionFailed => SofaError::PreconditionFailed { error, reason, meta },
+            StatusCode::TooManyRequests => SofaError::TooManyRequests { error, reason, meta },
+            _ => SofaError::ServerError {
+                status: status.as_u16(),
+                error,
+                reason,
+                meta,
+            },
+        }
+    }
+
+    /// Attaches the metadata of the response an error comes from. Errors
+    /// that don't come from a response are left as they are.
+    pub fn with_meta(mut self, response_meta: ResponseMeta) -> SofaError {
+        match self {
+            SofaError::NotFound { ref mut meta, .. }
+            | SofaError::Conflict { ref mut meta, .. }
+            | SofaError::Unauthorized { ref mut meta, .. }
+            | SofaError::Forbidden { ref mut meta, .. }
+            | SofaError::PreconditionFailed { ref mut meta, .. }
+            | SofaError::TooManyRequests { ref mut meta, .. }
+            | SofaError::ServerError { ref mut meta, .. } => **meta = response_meta,
+            _ => {}
+        }
+
+        self
+    }
+
+    /// HTTP status of the response the error comes from, if any
+    pub fn status(&self) -> Option<u16> {
+        match *self {
+            SofaError::NotFound { .. } => Some(404),
+            SofaError::Conflict { .. } => Some(409),
+            SofaError::Unauthorized { .. } => Some(401),
+            SofaError::Forbidden { .. } => Some(403),
+            SofaError::PreconditionFailed { .. } => Some(412),
+            SofaError::TooManyRequests { .. } => Some(429),
+            SofaError::ServerError { status, .. } => Some(status),
+            _ => None,
+        }
+    }
+
+    /// `error` field of the error body CouchDB sent, such as `not_found` or
+    /// `conflict`
+    pub fn error(&self) -> Option<&str> {
+        match *self {
+            SofaError::NotFound { ref error, .. }
+            | SofaError::Conflict { ref error, .. }
+            | SofaError::Unauthorized { ref error, .. }
+            | SofaError::Forbidden { ref error, .. }
+            | SofaError::PreconditionFailed { ref error, .. }
+            | SofaError::TooManyRequests { ref error, .. }
+            | SofaError::ServerError { ref error, .. } => Some(error),
+            _ => None,
+        }
+    }
+
+    /// `reason` field of the error body CouchDB sent, a human readable
+    /// explanation
+    pub fn reason(&self) -> Option<&str> {
+        match *self {
+            SofaError::NotFound { ref reason, .. }
+            | SofaError::Conflict { ref reason, .. }
+            | SofaError::Unauthorized { ref reason, .. }
+            | SofaError::Forbidden { ref reason, .. }
+            | SofaError::PreconditionFailed { ref reason, .. }
+            | SofaError::TooManyRequests { ref reason, .. }
+            | SofaError::ServerError { ref reason, .. } => Some(reason),
+            _ => None,
+        }
+    }
+
+    /// Metadata of the response the error comes from, if any
+    pub fn meta(&self) -> Option<&ResponseMeta> {
+        match *self {
+            SofaError::NotFound { ref meta, .. }
+            | SofaError::Conflict { ref meta, .. }
+            | SofaError::Unauthorized { ref meta, .. }
+            | SofaError::Forbidden { ref meta, .. }
+            | SofaError::PreconditionFailed { ref meta, .. }
+            | SofaError::TooManyRequests { ref meta, .. }
+            | SofaError::ServerError { ref meta, .. } => Some(&**meta),
+            _ => None,
+        }
+    }
+
+    /// `X-Couch-Request-ID` of the response the error comes from, to look
+    /// the failure up in the server logs
+    pub fn request_id(&self) -> Option<&str> {
+        self.meta().and_then(|meta| meta.request_id.as_deref())
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        matches!(*self, SofaError::NotFound { .. })
+    }
+
+    pub fn is_conflict(&self) -> bool {
+        matches!(*self, SofaError::Conflict { .. })
+    }
+}
+
+impl fmt::Display for SofaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SofaError::NotFound { ref reason, .. } => write!(f, "Not found: {}", reason),
+            SofaError::Conflict { ref reason, .. } => write!(f, "Document update conflict: {}", reason),
+            SofaError::Unauthorized { ref reason, .. } => write!(f, "Unauthorized: {}", reason),
+            SofaError::Forbidden { ref reason, .. } => write!(f, "Forbidden: {}", reason),
+            SofaError::PreconditionFailed { ref reason, .. } => write!(f, "Precondition failed: {}", reason),
+            SofaError::TooManyRequests { ref reason, .. } => write!(f, "Too many requests: {}", reason),
+            SofaError::ServerError { status, ref reason, .. } => write!(f, "Server error {}: {}", status, reason),
+            SofaError::Transport(ref err) => write!(f, "Transport error: {}", err),
+            SofaError::Serialization(ref err) => write!(f, "Serialization error: {}", err),
+            SofaError::Custom(ref message) => write!(f, "Custom error: {}", message),
+        }?;
+
+        match self.request_id() {
+            Some(request_id) => write!(f, " (request {})", request_id),
+            None => Ok(()),
+        }
+    }
+}
+
+impl StdError for SofaError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match *self {
+            SofaError::Transport(ref err) | SofaError::Serialization(ref err) => Some(&**err),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for SofaError {
+    fn from(err: reqwest::Error) -> SofaError {
+        if err.is_serialization() {
+            SofaError::Serialization(Box::new(err))
+        } else {
+            SofaError::Transport(Box::new(err))
+        }
+    }
+}
+
+impl From<serde_json::Error> for SofaError {
+    fn from(err: serde_json::Error) -> SofaError {
+        if err.is_io() {
+            SofaError::Transport(Box::new(err))
+        } else {
+            SofaError::Serialization(Box::new(err))
+        }
+    }
+}
+
+impl From<io::Error> for SofaError {
+    fn from(err: io::Error) -> SofaError {
+        SofaError::Transport(Box::new(err))
+    }
+}
+
+impl From<url::ParseError> for SofaError {
+    fn from(err: url::ParseError) -> SofaError {
+        SofaError::Custom(format!("invalid URL: {}", err))
+    }
+}
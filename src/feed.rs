@@ -0,0 +1,267 @@
+use std::io::{self, BufRead, BufReader};
+use std::marker::PhantomData;
+
+use error::SofaError;
+use reqwest::Response;
+use serde::de::DeserializeOwned;
+use serde_json::{self, Value};
+
+use database::*;
+use types::*;
+
+/// ContinuousFeed iterates over the events of a continuous feed
+/// (`feed=continuous`), one JSON object per line, as they are sent by the
+/// server. Heartbeats are skipped, and the iteration stops when the server
+/// closes the feed, its last sequence then being available through
+/// `last_seq`.
+///
+/// The feed is read through the client's timeout: make sure heartbeats are
+/// sent more often than it elapses, or use `Client::with_timeout`.
+pub struct ContinuousFeed<T> {
+    reader: BufReader<Response>,
+    last_seq: Option<Seq>,
+    done: bool,
+    _item: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> ContinuousFeed<T> {
+    pub fn new(response: Response) -> ContinuousFeed<T> {
+        ContinuousFeed {
+            reader: BufReader::new(response),
+            last_seq: None,
+            done: false,
+            _item: PhantomData,
+        }
+    }
+
+    /// Last sequence sent by the server when closing the feed
+    pub fn last_seq(&self) -> Option<&Seq> {
+        self.last_seq.as_ref()
+    }
+}
+
+impl<T: DeserializeOwned> Iterator for ContinuousFeed<T> {
+    type Item = Result<T, SofaError>;
+
+    fn next(&mut self) -> Option<Result<T, SofaError>> {
+        while !self.done {
+            let mut line = String::new();
+
+            match self.reader.read_line(&mut line) {
+                Ok(0) => self.done = true,
+                Ok(_) if line.trim().is_empty() => continue,
+                Ok(_) => {
+                    let value: Value = match serde_json::from_str(&line) {
+                        Ok(value) => value,
+                        Err(e) => return Some(Err(e.into())),
+                    };
+
+                    if let Some(last_seq) = value.get("last_seq") {
+                        self.last_seq = serde_json::from_value(last_seq.clone()).ok();
+                        self.done = true;
+                        continue;
+                    }
+
+                    return Some(serde_json::from_value(value).map_err(SofaError::from));
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e.into()));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// EventSourceFeed iterates over the events of an `eventsource` feed, parsing
+/// the server-sent events framing: each event is made of `data:` lines
+/// holding a JSON object, and an `id:` line holding its sequence. Comments
+/// and heartbeat events are skipped. The same timeout considerations as for
+/// `ContinuousFeed` apply.
+pub struct EventSourceFeed<T> {
+    reader: BufReader<Response>,
+    last_event_id: Option<String>,
+    done: bool,
+    _item: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> EventSourceFeed<T> {
+    pub fn new(response: Response) -> EventSourceFeed<T> {
+        EventSourceFeed {
+            reader: BufReader::new(response),
+            last_event_id: None,
+            done: false,
+            _item: PhantomData,
+        }
+    }
+
+    /// ID of the last event received, which is its sequence for changes
+    /// feeds
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.last_event_id.as_deref()
+    }
+}
+
+impl<T: DeserializeOwned> Iterator for EventSourceFeed<T> {
+    type Item = Result<T, SofaError>;
+
+    fn next(&mut self) -> Option<Result<T, SofaError>> {
+        let mut event = None;
+        let mut data = String::new();
+
+        while !self.done {
+            let mut line = String::new();
+
+            match self.reader.read_line(&mut line) {
+                Ok(0) => self.done = true,
+                Ok(_) => {
+                    let line = line.trim_end_matches(['\r', '\n']);
+
+                    if line.is_empty() {
+                        let is_heartbeat = event.take().is_some_and(|e: String| e == "heartbeat");
+                        if is_heartbeat || data.trim().is_empty() {
+                            data.clear();
+                            continue;
+                        }
+
+                        return Some(serde_json::from_str(&data).map_err(SofaError::from));
+                    }
+
+                    let (field, value) = match line.find(':') {
+                        Some(0) => continue,
+                        Some(i) => (&line[..i], line[i + 1..].trim_start()),
+                        None => (line, ""),
+                    };
+
+                    match field {
+                        "data" => {
+                            if !data.is_empty() {
+                                data.push('\n');
+                            }
+                            data.push_str(value);
+                        }
+                        "id" => self.last_event_id = Some(s!(value)),
+                        "event" => event = Some(s!(value)),
+                        _ => {}
+                    }
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e.into()));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// ChangesFeed follows the changes of a database through a continuous
+/// `_changes` feed. When the connection drops before the server closed the
+/// feed, it transparently reconnects from the last sequence it saw, up to
+/// `max_reconnects` times in a row.
+pub struct ChangesFeed {
+    db: Database,
+    params: ChangesParams,
+    feed: Option<ContinuousFeed<ChangeEvent>>,
+    last_seq: Option<Seq>,
+    max_reconnects: u32,
+    reconnects: u32,
+    done: bool,
+}
+
+impl ChangesFeed {
+    pub fn new(db: Database, mut params: ChangesParams) -> ChangesFeed {
+        params.feed = Some(FeedMode::Continuous);
+
+        ChangesFeed {
+            db,
+            params,
+            feed: None,
+            last_seq: None,
+            max_reconnects: 3,
+            reconnects: 0,
+            done: false,
+        }
+    }
+
+    /// Sets the number of reconnections attempted in a row, without any
+    /// change received in between, before giving up. Defaults to 3.
+    pub fn max_reconnects(mut self, max_reconnects: u32) -> ChangesFeed {
+        self.max_reconnects = max_reconnects;
+        self
+    }
+
+    /// Last sequence seen, to resume the feed from later on
+    pub fn last_seq(&self) -> Option<&Seq> {
+        self.last_seq.as_ref()
+    }
+
+    /// Drops the current connection, returning `false` when no reconnection
+    /// attempt is left
+    fn reconnect(&mut self) -> bool {
+        self.feed = None;
+        self.reconnects += 1;
+
+        self.reconnects <= self.max_reconnects
+    }
+}
+
+impl Iterator for ChangesFeed {
+    type Item = Result<ChangeEvent, SofaError>;
+
+    fn next(&mut self) -> Option<Result<ChangeEvent, SofaError>> {
+        while !self.done {
+            if self.feed.is_none() {
+                match self.db.changes_response(&self.params) {
+                    Ok(response) => self.feed = Some(ContinuousFeed::new(response)),
+                    Err(e) => {
+                        self.done = true;
+                        return Some(Err(e));
+                    }
+                }
+            }
+
+            let next = self.feed.as_mut().and_then(|feed| feed.next());
+            match next {
+                Some(Ok(event)) => {
+                    self.params.since = Some(event.seq.clone());
+                    self.last_seq = Some(event.seq.clone());
+                    self.reconnects = 0;
+
+                    return Some(Ok(event));
+                }
+                Some(Err(e)) => {
+                    let dropped = match e {
+                        SofaError::Transport(ref err) => err.is::<io::Error>(),
+                        _ => false,
+                    };
+                    if dropped && self.reconnect() {
+                        continue;
+                    }
+
+                    return Some(Err(e));
+                }
+                None => {
+                    let closed = self.feed.as_ref().and_then(|feed| feed.last_seq().cloned());
+
+                    match closed {
+                        Some(seq) => {
+                            self.last_seq = Some(seq);
+                            self.done = true;
+                        }
+                        None => {
+                            if !self.reconnect() {
+                                self.done = true;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,114 @@
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use reqwest;
+
+use error::SofaError;
+use request::read_json;
+use types::*;
+
+/// Default IBM Cloud IAM token endpoint
+pub const IAM_TOKEN_URL: &str = "https://iam.cloud.ibm.com/identity/token";
+
+/// Grant type used to exchange an API key against an access token
+const IAM_GRANT_TYPE: &str = "urn:ibm:params:oauth:grant-type:apikey";
+
+/// Lifetime assumed for tokens IAM sent without any, the one it grants by
+/// default
+const DEFAULT_TOKEN_LIFETIME: u64 = 3600;
+
+/// Access token cached by an `IamAuthenticator`
+#[derive(Debug, Clone)]
+struct CachedToken {
+    access_token: String,
+    refresh_at: Instant,
+}
+
+/// IamAuthenticator exchanges an IBM Cloud API key against IAM access tokens,
+/// and refreshes them before they expire. Once set on a `Client` with
+/// `ClientBuilder::iam_auth`, every request is sent with an
+/// `Authorization: Bearer` header.
+#[derive(Debug, Clone)]
+pub struct IamAuthenticator {
+    _client: reqwest::Client,
+    _token: Arc<RwLock<Option<CachedToken>>>,
+    api_key: String,
+    endpoint: String,
+}
+
+impl IamAuthenticator {
+    pub fn new(api_key: String) -> Result<IamAuthenticator, SofaError> {
+        IamAuthenticator::with_endpoint(api_key, s!(IAM_TOKEN_URL))
+    }
+
+    /// Creates an authenticator exchanging tokens against a custom IAM
+    /// endpoint (such as a staging environment)
+    pub fn with_endpoint(api_key: String, endpoint: String) -> Result<IamAuthenticator, SofaError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::new(10, 0))
+            .build()?;
+
+        Ok(IamAuthenticator {
+            _client: client,
+            _token: Arc::new(RwLock::new(None)),
+            api_key,
+            endpoint,
+        })
+    }
+
+    /// Returns a valid access token, requesting a new one from IAM when the
+    /// cached one reached 80% of its lifetime
+    pub fn token(&self) -> Result<String, SofaError> {
+        {
+            let guard = self._token.read().map_err(|_| SofaError::Custom(s!("IAM token lock poisoned")))?;
+            if let Some(ref token) = *guard {
+                if Instant::now() < token.refresh_at {
+                    return Ok(token.access_token.clone());
+                }
+            }
+        }
+
+        let token = self.request_token()?;
+        let access_token = token.access_token.clone();
+
+        let mut guard = self._token.write().map_err(|_| SofaError::Custom(s!("IAM token lock poisoned")))?;
+        *guard = Some(token);
+
+        Ok(access_token)
+    }
+
+    fn request_token(&self) -> Result<CachedToken, SofaError> {
+        let requested_at = Instant::now();
+        let response = self._client.post(&self.endpoint)
+            .header(reqwest::header::Accept::json())
+            .form(&[("grant_type", IAM_GRANT_TYPE), ("apikey", &self.api_key)])
+            .send()?;
+
+        let data: IamToken = read_json(response)?;
+
+        match data.access_token {
+            Some(access_token) => {
+                let expiration = data.expiration;
+                let lifetime = data.expires_in
+                    .or_else(|| expiration.map(seconds_until))
+                    .unwrap_or(DEFAULT_TOKEN_LIFETIME);
+
+                Ok(CachedToken {
+                    access_token,
+                    refresh_at: requested_at + Duration::from_secs(lifetime * 4 / 5),
+                })
+            },
+            None => {
+                let err = data.error_message.or(data.error_code).unwrap_or(s!("unspecified error"));
+                Err(SofaError::Custom(err))
+            },
+        }
+    }
+}
+
+/// Seconds left until `expiration`, given as a UNIX timestamp
+fn seconds_until(expiration: u64) -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs()).unwrap_or(0);
+
+    expiration.saturating_sub(now)
+}
@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use reqwest::{self, Method, Response, StatusCode, Url};
+
+use middleware::Middleware;
+
+/// Kind of CouchDB endpoint a request was sent to, coarse enough to be used as
+/// a metrics label
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndpointClass {
+    /// Server-wide endpoints: `/`, `/_all_dbs`, `/_uuids`, `/_node`...
+    Server,
+    /// `/_session`
+    Session,
+    /// A database itself, or one of its maintenance endpoints
+    Database,
+    /// A document, local documents included
+    Document,
+    /// An attachment of a document
+    Attachment,
+    /// A design document
+    DesignDocument,
+    /// A view, list, show, update handler or search index of a design
+    /// document
+    View,
+    /// Mango endpoints: `_find`, `_explain` and `_index`
+    Mango,
+    /// Bulk endpoints: `_all_docs`, `_bulk_docs`, `_bulk_get`...
+    Bulk,
+    /// `_changes` feeds
+    Changes,
+    /// Partitioned database endpoints
+    Partition,
+}
+
+impl EndpointClass {
+    /// Classifies the endpoint `url` addresses on the server reachable at
+    /// `base_path`
+    pub fn from_url(base_path: &str, url: &Url) -> EndpointClass {
+        let path = url.path();
+        let path = path.strip_prefix(base_path).unwrap_or(path);
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        match segments.as_slice() {
+            [] => EndpointClass::Server,
+            ["_session", ..] => EndpointClass::Session,
+            [db, ..] if db.starts_with('_') && !["_users", "_replicator", "_global_changes"].contains(db) => {
+                EndpointClass::Server
+            }
+            [_] => EndpointClass::Database,
+            [_, "_design", _] => EndpointClass::DesignDocument,
+            [_, "_design", ..] => EndpointClass::View,
+            [_, "_local", ..] => EndpointClass::Document,
+            [_, "_changes", ..] => EndpointClass::Changes,
+            [_, "_partition", ..] => EndpointClass::Partition,
+            [_, "_find", ..] | [_, "_explain", ..] | [_, "_index", ..] => EndpointClass::Mango,
+            [_, "_all_docs", ..]
+            | [_, "_bulk_docs", ..]
+            | [_, "_bulk_get", ..]
+            | [_, "_purge", ..]
+            | [_, "_revs_diff", ..]
+            | [_, "_missing_revs", ..] => EndpointClass::Bulk,
+            [_, endpoint, ..] if endpoint.starts_with('_') => EndpointClass::Database,
+            [_, _] => EndpointClass::Document,
+            _ => EndpointClass::Attachment,
+        }
+    }
+
+    /// Returns a short lowercase name of the class, suitable as a label value
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            EndpointClass::Server => "server",
+            EndpointClass::Session => "session",
+            EndpointClass::Database => "database",
+            EndpointClass::Document => "document",
+            EndpointClass::Attachment => "attachment",
+            EndpointClass::DesignDocument => "design_document",
+            EndpointClass::View => "view",
+            EndpointClass::Mango => "mango",
+            EndpointClass::Bulk => "bulk",
+            EndpointClass::Changes => "changes",
+            EndpointClass::Partition => "partition",
+        }
+    }
+}
+
+/// Metrics is invoked by the client once per HTTP call, retries included, so
+/// that request rates, latencies and error rates can be exported, e.g. to
+/// Prometheus. `status` is `None` when no response was received.
+pub trait Metrics: Send + Sync {
+    fn record(&self, endpoint: EndpointClass, method: &Method, status: Option<StatusCode>, elapsed: Duration);
+}
+
+impl<F> Metrics for F
+where
+    F: Fn(EndpointClass, &Method, Option<StatusCode>, Duration) + Send + Sync,
+{
+    fn record(&self, endpoint: EndpointClass, method: &Method, status: Option<StatusCode>, elapsed: Duration) {
+        self(endpoint, method, status, elapsed)
+    }
+}
+
+/// Middleware feeding a `Metrics` implementation
+pub(crate) struct MetricsRecorder<M> {
+    metrics: M,
+    base_path: String,
+}
+
+impl<M: Metrics> MetricsRecorder<M> {
+    pub fn new(metrics: M, base_uri: &str) -> MetricsRecorder<M> {
+        let base_path = Url::parse(base_uri)
+            .map(|url| s!(url.path().trim_end_matches('/')))
+            .unwrap_or_default();
+
+        MetricsRecorder { metrics, base_path }
+    }
+}
+
+impl<M: Metrics> Middleware for MetricsRecorder<M> {
+    fn after_response(&self, method: &Method, url: &Url, response: &Response, elapsed: Duration) {
+        let endpoint = EndpointClass::from_url(&self.base_path, url);
+        self.metrics.record(endpoint, method, Some(response.status()), elapsed);
+    }
+
+    fn on_error(&self, method: &Method, url: &Url, _error: &reqwest::Error, elapsed: Duration) {
+        let endpoint = EndpointClass::from_url(&self.base_path, url);
+        self.metrics.record(endpoint, method, None, elapsed);
+    }
+}
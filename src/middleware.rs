@@ -0,0 +1,97 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::{self, Method, Response, Url};
+
+/// Middleware lets applications hook into every HTTP call made by a `Client`,
+/// to alter outgoing requests (custom authentication schemes, extra headers)
+/// or observe their outcome (audit logs, metrics). Hooks run for each attempt
+/// of a request, retries included, in the order they were registered.
+pub trait Middleware: Send + Sync {
+    /// Called right before a request is sent
+    fn before_request(&self, _request: &mut reqwest::Request) {}
+
+    /// Called once a response has been received, after `elapsed`
+    fn after_response(&self, _method: &Method, _url: &Url, _response: &Response, _elapsed: Duration) {}
+
+    /// Called when a request failed without a response, after `elapsed`
+    fn on_error(&self, _method: &Method, _url: &Url, _error: &reqwest::Error, _elapsed: Duration) {}
+}
+
+/// Middleware made of a function called before each request
+struct RequestHook<F>(F);
+
+impl<F> Middleware for RequestHook<F>
+where
+    F: Fn(&mut reqwest::Request) + Send + Sync,
+{
+    fn before_request(&self, request: &mut reqwest::Request) {
+        (self.0)(request)
+    }
+}
+
+/// Middleware made of a function called after each response
+struct ResponseHook<F>(F);
+
+impl<F> Middleware for ResponseHook<F>
+where
+    F: Fn(&Method, &Url, &Response, Duration) + Send + Sync,
+{
+    fn after_response(&self, method: &Method, url: &Url, response: &Response, elapsed: Duration) {
+        (self.0)(method, url, response, elapsed)
+    }
+}
+
+/// Ordered list of the middlewares registered on a client
+#[derive(Clone, Default)]
+pub(crate) struct Middlewares(Vec<Arc<dyn Middleware>>);
+
+impl Middlewares {
+    pub fn push<M: Middleware + 'static>(&mut self, middleware: M) {
+        self.0.push(Arc::new(middleware));
+    }
+
+    pub fn push_request_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(&mut reqwest::Request) + Send + Sync + 'static,
+    {
+        self.push(RequestHook(hook));
+    }
+
+    pub fn push_response_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(&Method, &Url, &Response, Duration) + Send + Sync + 'static,
+    {
+        self.push(ResponseHook(hook));
+    }
+
+    #[cfg(feature = "async")]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn before_request(&self, request: &mut reqwest::Request) {
+        for middleware in &self.0 {
+            middleware.before_request(request);
+        }
+    }
+
+    pub fn after_response(&self, method: &Method, url: &Url, response: &Response, elapsed: Duration) {
+        for middleware in &self.0 {
+            middleware.after_response(method, url, response, elapsed);
+        }
+    }
+
+    pub fn on_error(&self, method: &Method, url: &Url, error: &reqwest::Error, elapsed: Duration) {
+        for middleware in &self.0 {
+            middleware.on_error(method, url, error, elapsed);
+        }
+    }
+}
+
+impl fmt::Debug for Middlewares {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Middlewares({})", self.0.len())
+    }
+}
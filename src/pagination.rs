@@ -0,0 +1,243 @@
+use std::collections::VecDeque;
+
+use serde::de::DeserializeOwned;
+use serde_json;
+use serde_json::Value;
+
+use client::*;
+use database::*;
+use document::*;
+use error::SofaError;
+use types::*;
+
+/// Iterator over the databases of a server, fetched a page at a time through
+/// `/_all_dbs`. Each page starts at the last name of the previous one, so
+/// databases created or deleted while iterating don't shift the next pages.
+#[derive(Debug)]
+pub struct AllDbsIter {
+    client: Client,
+    params: AllDbsParams,
+    page_size: u64,
+    page: VecDeque<String>,
+    last: Option<String>,
+    yielded: u64,
+    done: bool,
+}
+
+impl AllDbsIter {
+    pub fn new(client: Client, params: AllDbsParams, page_size: u64) -> AllDbsIter {
+        AllDbsIter {
+            client,
+            params,
+            page_size: page_size.max(1),
+            page: VecDeque::new(),
+            last: None,
+            yielded: 0,
+            done: false,
+        }
+    }
+
+    fn fetch_page(&mut self) -> Result<(), SofaError> {
+        let mut params = self.params.clone();
+        let mut limit = self.page_size;
+
+        if let Some(total) = self.params.limit {
+            limit = limit.min(total - self.yielded);
+        }
+
+        if let Some(ref last) = self.last {
+            params.start_key = Some(last.clone());
+            params.skip = Some(1);
+        }
+        params.limit = Some(limit);
+
+        let names = self.client.list_dbs_params(&params)?;
+        if (names.len() as u64) < limit {
+            self.done = true;
+        }
+
+        self.last = names.last().cloned().or_else(|| self.last.take());
+        self.page.extend(names);
+
+        Ok(())
+    }
+}
+
+impl Iterator for AllDbsIter {
+    type Item = Result<String, SofaError>;
+
+    fn next(&mut self) -> Option<Result<String, SofaError>> {
+        if self.params.limit.is_some_and(|total| self.yielded >= total) {
+            return None;
+        }
+
+        if self.page.is_empty() && !self.done {
+            if let Err(e) = self.fetch_page() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+
+        let name = self.page.pop_front()?;
+        self.yielded += 1;
+
+        Some(Ok(name))
+    }
+}
+
+/// Iterator over the documents matching a Mango query, fetched a page at a
+/// time through `/{db}/_find`, see `Database::find_paged`
+#[derive(Debug)]
+pub struct FindIter {
+    db: Database,
+    query: FindQuery,
+    page: VecDeque<Document>,
+    done: bool,
+}
+
+impl FindIter {
+    pub fn new(db: Database, query: FindQuery) -> FindIter {
+        FindIter {
+            db,
+            query,
+            page: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Bookmark to resume the iteration from later on, once the current page
+    /// is consumed
+    pub fn bookmark(&self) -> Option<&str> {
+        self.query.bookmark.as_deref()
+    }
+
+    fn fetch_page(&mut self) -> Result<(), SofaError> {
+        let result: FindResult = self.db.find_raw(&self.query)?;
+        if let Some(err) = result.error {
+            return Err(SofaError::Custom(result.reason.unwrap_or(err)));
+        }
+
+        let docs = result.docs.unwrap_or_default();
+        let full = self.query.limit.map_or(!docs.is_empty(), |limit| docs.len() as u64 >= limit);
+        if !full || result.bookmark.is_none() {
+            self.done = true;
+        }
+
+        // Skipped documents are accounted for by the bookmark
+        self.query.skip = None;
+        self.query.bookmark = result.bookmark;
+        for doc in docs {
+            if !doc["_id"].as_str().unwrap_or_default().starts_with('_') {
+                self.page.push_back(Document::try_new(doc)?);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Iterator for FindIter {
+    type Item = Result<Document, SofaError>;
+
+    fn next(&mut self) -> Option<Result<Document, SofaError>> {
+        while self.page.is_empty() && !self.done {
+            if let Err(e) = self.fetch_page() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+
+        self.page.pop_front().map(Ok)
+    }
+}
+
+/// Iterator over the rows of a view, fetched a page at a time, see
+/// `Database::query_view_paged`. Each page is fetched with one row more than
+/// it holds, whose key and document ID the next page starts at, so rows
+/// emitted while iterating don't shift the next pages. Queries with `keys`
+/// are paged with `skip` instead.
+#[derive(Debug)]
+pub struct ViewIter<K, V> {
+    db: Database,
+    ddoc: String,
+    view: String,
+    query: ViewQuery,
+    page_size: u64,
+    page: VecDeque<ViewRow<K, V>>,
+    yielded: u64,
+    done: bool,
+}
+
+impl<K: DeserializeOwned, V: DeserializeOwned> ViewIter<K, V> {
+    pub fn new(db: Database, ddoc: &str, view: &str, query: ViewQuery, page_size: u64) -> ViewIter<K, V> {
+        ViewIter {
+            db,
+            ddoc: s!(ddoc),
+            view: s!(view),
+            query,
+            page_size: page_size.max(1),
+            page: VecDeque::new(),
+            yielded: 0,
+            done: false,
+        }
+    }
+
+    fn fetch_page(&mut self) -> Result<(), SofaError> {
+        let mut limit = self.page_size;
+        if let Some(total) = self.query.limit {
+            limit = limit.min(total - self.yielded);
+        }
+
+        let mut query = self.query.clone();
+        query.limit = Some(limit + 1);
+        let mut rows = self.db.query_view::<Value, Value>(&self.ddoc, &self.view, &query)?.rows;
+
+        if rows.len() as u64 > limit {
+            let next = rows.pop().expect("a page holds at least one row");
+            if self.query.keys.is_some() {
+                // Keys can't be combined with a start key
+                self.query.skip = Some(self.query.skip.unwrap_or(0) + limit);
+            } else {
+                self.query.start_key = Some(next.key);
+                self.query.start_key_doc_id = next.id.map(String::from);
+                // Skipped rows are accounted for by the start key
+                self.query.skip = None;
+            }
+        } else {
+            self.done = true;
+        }
+
+        for row in rows {
+            self.page.push_back(ViewRow {
+                id: row.id,
+                key: serde_json::from_value(row.key)?,
+                value: serde_json::from_value(row.value)?,
+                doc: row.doc,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl<K: DeserializeOwned, V: DeserializeOwned> Iterator for ViewIter<K, V> {
+    type Item = Result<ViewRow<K, V>, SofaError>;
+
+    fn next(&mut self) -> Option<Result<ViewRow<K, V>, SofaError>> {
+        if self.query.limit.is_some_and(|total| self.yielded >= total) {
+            return None;
+        }
+
+        if self.page.is_empty() && !self.done {
+            if let Err(e) = self.fetch_page() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+
+        let row = self.page.pop_front()?;
+        self.yielded += 1;
+
+        Some(Ok(row))
+    }
+}
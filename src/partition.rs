@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use error::SofaError;
+use serde::Serialize;
+use serde_json::Value;
+
+use client::*;
+use database::find_result_documents;
+use document::*;
+use request::*;
+use types::*;
+
+/// Partition is a handle on the documents of a partitioned database sharing
+/// the same ID prefix (`partition:id`). Queries made through it only hit the
+/// shard holding the partition, instead of being run database-wide.
+#[derive(Debug, Clone)]
+pub struct Partition {
+    _client: Client,
+    db_name: String,
+    name: String,
+}
+
+impl Partition {
+    pub fn new(db_name: String, name: String, client: Client) -> Partition {
+        Partition {
+            _client: client,
+            db_name,
+            name,
+        }
+    }
+
+    fn create_partition_path(&self, endpoint: &str) -> String {
+        format!("{}/_partition/{}/{}", self.db_name, encode_path_segment(&self.name), endpoint)
+    }
+
+    /// Returns the name of the partition
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Fetches the document counts and sizes of the partition
+    pub fn info(&self) -> Result<PartitionInfo, SofaError> {
+        let path = format!("{}/_partition/{}", self.db_name, encode_path_segment(&self.name));
+        let response = self._client.get(path, None)?.send()?;
+
+        read_json(response)
+    }
+
+    /// Gets all the documents of the partition
+    pub fn get_all(&self) -> Result<DocumentCollection, SofaError> {
+        self.get_all_params(None)
+    }
+
+    /// Gets all the documents of the partition, with applied parameters. Parameters description can be found here: http://docs.couchdb.org/en/latest/api/ddoc/views.html#api-ddoc-view
+    pub fn get_all_params(&self, params: Option<HashMap<String, String>>) -> Result<DocumentCollection, SofaError> {
+        let mut options = params.unwrap_or_default();
+        options.insert(s!("include_docs"), s!("true"));
+
+        let response = self._client
+            .get(self.create_partition_path("_all_docs"), Some(options))?
+            .send()?;
+
+        Ok(DocumentCollection::new(read_json(response)?))
+    }
+
+    /// Finds documents of the partition through a Mango query. Parameters here http://docs.couchdb.org/en/latest/api/database/find.html
+    pub fn find<Q: Serialize>(&self, params: Q) -> Result<DocumentCollection, SofaError> {
+        let response = self._client
+            .post(self.create_partition_path("_find"), js!(&params))?
+            .send()?;
+
+        let data: FindResult = read_json(response)?;
+        find_result_documents(data)
+    }
+
+    /// Queries a view over the documents of the partition, returning the raw
+    /// view response. `ddoc` can be given with or without its `_design/`
+    /// prefix. Parameters description can be found here: http://docs.couchdb.org/en/latest/api/ddoc/views.html#api-ddoc-view
+    pub fn query_view(
+        &self,
+        ddoc: &str,
+        view: &str,
+        params: Option<HashMap<String, String>>,
+    ) -> Result<Value, SofaError> {
+        let path = self.create_partition_path(&format!(
+            "_design/{}/_view/{}",
+            encode_design_name(ddoc),
+            encode_path_segment(view)
+        ));
+        let response = self._client.get(path, params)?.send()?;
+
+        read_json(response)
+    }
+}
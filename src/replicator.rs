@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use serde_json::{from_value, Value};
+
+use client::*;
+use error::SofaError;
+use request::*;
+use types::*;
+
+/// Name of the default replicator database
+pub const REPLICATOR_DB: &str = "_replicator";
+
+/// Replicator manages persistent replications, stored as documents of a
+/// replicator database and run by the server's replication scheduler until
+/// they are cancelled.
+#[derive(Debug, Clone)]
+pub struct Replicator {
+    _client: Client,
+    name: String,
+}
+
+impl Replicator {
+    /// Manages the replications of the `name` replicator database, which is
+    /// either `_replicator` or ends with `/_replicator`
+    pub fn new(name: String, client: Client) -> Replicator {
+        Replicator {
+            _client: client,
+            name,
+        }
+    }
+
+    fn create_document_path(&self, id: &str) -> String {
+        format!("{}/{}", encode_path_segment(&self.name), encode_doc_id(id))
+    }
+
+    /// Creates the replicator database if it doesn't exist yet
+    pub fn ensure_db(&self) -> Result<bool, SofaError> {
+        let path = encode_path_segment(&self.name);
+        let head_response = self._client.head(path.clone(), None)?.send()?;
+
+        if head_response.status().is_success() {
+            return Ok(false);
+        }
+
+        let response = self._client.put(path, String::new())?.send()?;
+        let s: CouchResponse = read_json(response)?;
+
+        Ok(s.ok.unwrap_or(false))
+    }
+
+    /// Starts a persistent replication. The document is returned with its
+    /// revision, a conflict being reported when a replication with the same
+    /// ID already exists.
+    pub fn create(&self, mut doc: ReplicationDocument) -> Result<ReplicationDocument, SofaError> {
+        let response = self._client.put(self.create_document_path(&doc.id), js!(&doc))?.send()?;
+        let data: DocumentCreatedResult = read_json(response)?;
+
+        match data.ok {
+            Some(true) => {
+                doc.rev = data.rev;
+                Ok(doc)
+            }
+            _ => {
+                let err = data.reason.or(data.error).unwrap_or(s!("unspecified error"));
+                Err(SofaError::Custom(err))
+            }
+        }
+    }
+
+    /// Fetches a replication document, along with the state the scheduler
+    /// recorded in its `replication_*` fields
+    pub fn get(&self, id: &str) -> Result<ReplicationDocument, SofaError> {
+        let response = self._client.get(self.create_document_path(id), None)?.send()?;
+
+        read_json(response)
+    }
+
+    /// Lists the replication documents of the database
+    pub fn list(&self) -> Result<Vec<ReplicationDocument>, SofaError> {
+        let mut args = HashMap::new();
+        args.insert(s!("include_docs"), s!("true"));
+
+        let path = format!("{}/_all_docs", encode_path_segment(&self.name));
+        let response = self._client.get(path, Some(args))?.send()?;
+        let data: Value = read_json(response)?;
+
+        let rows = data["rows"].as_array().cloned().unwrap_or_default();
+        rows.into_iter()
+            .filter(|row| !row["id"].as_str().unwrap_or("").starts_with("_design/"))
+            .map(|row| Ok(from_value(row["doc"].clone())?))
+            .collect()
+    }
+
+    /// Fetches the detailed state of a replication from the scheduler
+    pub fn state(&self, id: &str) -> Result<SchedulerDoc, SofaError> {
+        self._client.scheduler_doc(&self.name, id)
+    }
+
+    /// Cancels a replication by deleting its document
+    pub fn cancel(&self, id: &str) -> Result<bool, SofaError> {
+        let doc = self.get(id)?;
+
+        let mut args = HashMap::new();
+        args.insert(s!("rev"), doc.rev.unwrap_or_default());
+
+        let response = self._client.delete(self.create_document_path(id), Some(args))?.send()?;
+        let s: CouchResponse = read_json(response)?;
+
+        Ok(s.ok.unwrap_or(false))
+    }
+}
@@ -0,0 +1,174 @@
+use std::io::Read;
+
+use serde::de::DeserializeOwned;
+use serde_json::{self, Value};
+
+use reqwest::header::{ETag, Header, Headers};
+use reqwest::{Body, Method, Response, StatusCode};
+
+use client::*;
+use error::SofaError;
+use types::{ResponseMeta, WithMeta};
+
+/// CouchRequest is a request to the CouchDB server being prepared. Unlike
+/// reqwest's `RequestBuilder`, it can be sent more than once, which lets the
+/// client retry it according to its `RetryPolicy`.
+#[derive(Debug, Clone)]
+pub struct CouchRequest<'a> {
+    client: &'a Client,
+    pub method: Method,
+    pub uri: String,
+    pub headers: Headers,
+    pub body: Option<Vec<u8>>,
+}
+
+impl<'a> CouchRequest<'a> {
+    pub fn new(client: &'a Client, method: Method, uri: String) -> CouchRequest<'a> {
+        CouchRequest {
+            client,
+            method,
+            uri,
+            headers: Headers::new(),
+            body: None,
+        }
+    }
+
+    /// Sets a header, replacing any previous value
+    pub fn header<H: Header>(&mut self, header: H) -> &mut CouchRequest<'a> {
+        self.headers.set(header);
+        self
+    }
+
+    /// Sets several headers, replacing any previous values
+    pub fn headers(&mut self, headers: Headers) -> &mut CouchRequest<'a> {
+        self.headers.extend(headers.iter());
+        self
+    }
+
+    pub fn body<T: Into<Vec<u8>>>(&mut self, body: T) -> &mut CouchRequest<'a> {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Sends the request through the client it was created by
+    pub fn send(&mut self) -> Result<Response, SofaError> {
+        self.client.execute(self)
+    }
+
+    /// Sends the request along a streamed body, replacing any body set
+    /// before. Such requests are never retried, as their body can't be read
+    /// again.
+    pub fn send_body(&mut self, body: Body) -> Result<Response, SofaError> {
+        self.client.execute_with_body(self, body)
+    }
+}
+
+/// Passes a successful response through, or turns the error body CouchDB sent
+/// along an unsuccessful one into an `Err`
+pub(crate) fn ensure_success(response: Response) -> Result<Response, SofaError> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+
+    Err(response_error(response))
+}
+
+/// Turns an unsuccessful response into the `SofaError` of its status, out of
+/// the error body CouchDB sent along
+pub(crate) fn response_error(mut response: Response) -> SofaError {
+    let meta = response_meta(response.headers());
+    let mut body = Vec::new();
+    let _ = response.read_to_end(&mut body);
+
+    status_error(response.status(), &body).with_meta(meta)
+}
+
+/// Maps an unsuccessful status and its body to a `SofaError`. The body is
+/// read as the `{"error", "reason"}` object CouchDB sends, tolerating other
+/// shapes: fields that aren't strings are kept as JSON, and a body that isn't
+/// JSON at all, as sent by a proxy, becomes the reason.
+pub(crate) fn status_error(status: StatusCode, body: &[u8]) -> SofaError {
+    let (error, reason) = match serde_json::from_slice::<Value>(body) {
+        Ok(Value::Object(mut fields)) => (
+            fields.remove("error").and_then(error_field),
+            fields.remove("reason").and_then(error_field),
+        ),
+        Ok(_) => (None, None),
+        Err(_) => {
+            let text = String::from_utf8_lossy(body).trim().to_string();
+            (None, if text.is_empty() { None } else { Some(text) })
+        }
+    };
+
+    let error = error.unwrap_or_else(|| s!(status.canonical_reason().unwrap_or("unknown_error")));
+    let reason = reason.unwrap_or_else(|| error.clone());
+
+    SofaError::from_status(status, error, reason)
+}
+
+fn error_field(value: Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(field) => Some(field),
+        field => Some(field.to_string()),
+    }
+}
+
+/// Turns the unsuccessful response to a write of the document `id` into a
+/// `SofaError`, a `Conflict` holding `id` on a 409
+pub(crate) fn conflict_error(id: &str, response: Response) -> SofaError {
+    with_conflict_id(response_error(response), id)
+}
+
+/// Sets the ID of the document `id` on a `Conflict`, leaving other errors as
+/// they are
+pub(crate) fn with_conflict_id(err: SofaError, id: &str) -> SofaError {
+    match err {
+        SofaError::Conflict { error, reason, meta, .. } => SofaError::Conflict {
+            id: Some(s!(id)),
+            error,
+            reason,
+            meta,
+        },
+        err => err,
+    }
+}
+
+/// Deserializes the JSON body of a successful response, see `ensure_success`
+pub(crate) fn read_json<T: DeserializeOwned>(response: Response) -> Result<T, SofaError> {
+    let mut response = ensure_success(response)?;
+
+    Ok(response.json()?)
+}
+
+/// Deserializes the JSON body of a successful response along with its
+/// metadata, see `read_json`
+pub(crate) fn read_json_with_meta<T: DeserializeOwned>(response: Response) -> Result<WithMeta<T>, SofaError> {
+    let meta = response_meta(response.headers());
+
+    Ok(WithMeta {
+        value: read_json(response)?,
+        meta,
+    })
+}
+
+/// Reads the headers of a response worth keeping, see `ResponseMeta`
+pub(crate) fn response_meta(headers: &Headers) -> ResponseMeta {
+    ResponseMeta {
+        request_id: header_value(headers, "X-Couch-Request-ID"),
+        body_time: header_value(headers, "X-CouchDB-Body-Time").and_then(|time| time.parse().ok()),
+        etag: headers.get::<ETag>().map(|etag| s!(etag.tag())),
+    }
+}
+
+/// Value of the raw header `name` of a response, when it is set once
+pub(crate) fn raw_header(response: &Response, name: &str) -> Option<String> {
+    header_value(response.headers(), name)
+}
+
+fn header_value(headers: &Headers, name: &str) -> Option<String> {
+    headers
+        .get_raw(name)
+        .and_then(|raw| raw.one())
+        .map(|value| String::from_utf8_lossy(value).into_owned())
+}
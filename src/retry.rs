@@ -0,0 +1,87 @@
+use std::cmp;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::time::Duration;
+
+use reqwest::{self, StatusCode};
+
+/// RetryPolicy describes how idempotent requests (`GET`, `HEAD`, `PUT`,
+/// `DELETE`...) are retried when they fail transiently, either through a
+/// connection error or one of the `retry_on` statuses. Attempts are spaced
+/// with an exponential backoff, honoring the `Retry-After` header sent along
+/// the response when there is one.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, the first one included
+    pub max_attempts: u32,
+    /// Delay before the second attempt, doubled on each following one
+    pub initial_backoff: Duration,
+    /// Upper bound of the delay between two attempts
+    pub max_backoff: Duration,
+    /// Randomizes each delay between half and all of its value, so that
+    /// clients failing together don't retry together
+    pub jitter: bool,
+    /// Response statuses considered transient
+    pub retry_on: Vec<StatusCode>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            jitter: true,
+            retry_on: vec![
+                StatusCode::TooManyRequests,
+                StatusCode::BadGateway,
+                StatusCode::ServiceUnavailable,
+                StatusCode::GatewayTimeout,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Checks if a response status is worth another attempt
+    pub fn should_retry_status(&self, status: StatusCode) -> bool {
+        self.retry_on.contains(&status)
+    }
+
+    /// Checks if a request error is worth another attempt, which is the case
+    /// of connection errors and timeouts
+    pub fn should_retry_error(&self, err: &reqwest::Error) -> bool {
+        err.is_http() || err.get_ref().is_some_and(|e| e.is::<io::Error>())
+    }
+
+    /// Computes the delay to wait for after the `attempt`-th attempt failed
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let delay = self.initial_backoff.checked_mul(factor).unwrap_or(self.max_backoff);
+        let delay = cmp::min(delay, self.max_backoff);
+
+        if !self.jitter {
+            return delay;
+        }
+
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u32(attempt);
+        let ratio = 0.5 + (hasher.finish() % 1000) as f64 / 2000.0;
+
+        Duration::from_millis((duration_ms(delay) as f64 * ratio) as u64)
+    }
+
+    /// Computes the delay to wait for after the `attempt`-th attempt failed
+    /// with `response`, honoring its `Retry-After` header within `max_backoff`
+    pub fn response_backoff(&self, attempt: u32, response: &reqwest::Response) -> Duration {
+        match response.headers().get::<reqwest::header::RetryAfter>() {
+            Some(&reqwest::header::RetryAfter::Delay(delay)) => cmp::min(delay, self.max_backoff),
+            _ => self.backoff(attempt),
+        }
+    }
+}
+
+fn duration_ms(d: Duration) -> u64 {
+    d.as_secs() * 1000 + u64::from(d.subsec_millis())
+}
@@ -0,0 +1,107 @@
+use std::ops::{Deref, DerefMut};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use types::*;
+
+/// CouchDocument is implemented by the types `Database` can save and get
+/// directly, such as `TypedDocument` or your own structs, through
+/// `#[derive(CouchDocument)]` when the `derive` feature is enabled
+pub trait CouchDocument: Serialize + DeserializeOwned {
+    /// Document ID
+    fn id(&self) -> &str;
+
+    /// Document revision, if the document was saved already
+    fn rev(&self) -> Option<&str>;
+
+    fn set_id(&mut self, id: String);
+
+    fn set_rev(&mut self, rev: String);
+}
+
+/// TypedDocument carries the `_id` and `_rev` of a document alongside its
+/// contents, deserialized into a struct of your own instead of a raw JSON
+/// value. The fields of `T` are flattened into the document.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct TypedDocument<T> {
+    /// Document ID
+    pub _id: DocumentId,
+
+    /// Document revision, empty until the document is first saved
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub _rev: String,
+
+    #[serde(flatten)]
+    pub data: T,
+}
+
+impl<T> TypedDocument<T> {
+    /// Wraps `data` into a new document, not saved yet
+    pub fn new(id: &str, data: T) -> TypedDocument<T> {
+        TypedDocument {
+            _id: id.into(),
+            _rev: s!(""),
+            data,
+        }
+    }
+
+    /// Returns the contents of the document, dropping its id and revision
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> CouchDocument for TypedDocument<T> {
+    fn id(&self) -> &str {
+        &self._id
+    }
+
+    fn rev(&self) -> Option<&str> {
+        if self._rev.is_empty() {
+            None
+        } else {
+            Some(&self._rev)
+        }
+    }
+
+    fn set_id(&mut self, id: String) {
+        self._id = id.into();
+    }
+
+    fn set_rev(&mut self, rev: String) {
+        self._rev = rev;
+    }
+}
+
+impl CouchDocument for DesignDocument {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn rev(&self) -> Option<&str> {
+        self.rev.as_deref()
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.id = id;
+    }
+
+    fn set_rev(&mut self, rev: String) {
+        self.rev = Some(rev);
+    }
+}
+
+impl<T> Deref for TypedDocument<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.data
+    }
+}
+
+impl<T> DerefMut for TypedDocument<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+}
@@ -0,0 +1,23 @@
+/// Attachment of a document, as listed in its `_attachments` field
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct Attachment {
+    pub content_type: String,
+    /// Digest of the contents, e.g. `md5-...`
+    pub digest: Option<String>,
+    /// Length of the contents, in bytes
+    pub length: Option<u64>,
+    /// Generation of the document revision the attachment was last changed
+    /// in
+    pub revpos: Option<u64>,
+    /// Whether the contents were left out, which is the default
+    #[serde(default)]
+    pub stub: bool,
+    /// Compression of the stored contents, e.g. `gzip`, included with
+    /// `att_encoding_info`
+    pub encoding: Option<String>,
+    /// Length of the stored contents, in bytes, included with
+    /// `att_encoding_info`
+    pub encoded_length: Option<u64>,
+    /// Base64-encoded contents, included with `attachments`
+    pub data: Option<String>
+}
@@ -0,0 +1,86 @@
+use serde_json::Value;
+
+/// Document, or revision of a document, requested from `/{db}/_bulk_get`
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct BulkGetItem {
+    pub id: String,
+    /// Revision to fetch, the current one when missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>
+}
+
+impl BulkGetItem {
+    pub fn new(id: &str, rev: Option<&str>) -> BulkGetItem {
+        BulkGetItem {
+            id: s!(id),
+            rev: rev.map(|r| s!(r))
+        }
+    }
+}
+
+/// Document to remove through `Database::bulk_remove`, at a known revision,
+/// or at its current one when `rev` is missing
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct BulkRemoveItem {
+    pub id: String,
+    pub rev: Option<String>
+}
+
+impl BulkRemoveItem {
+    pub fn new(id: &str, rev: Option<&str>) -> BulkRemoveItem {
+        BulkRemoveItem {
+            id: s!(id),
+            rev: rev.map(|r| s!(r))
+        }
+    }
+}
+
+impl<'a> From<&'a str> for BulkRemoveItem {
+    fn from(id: &'a str) -> BulkRemoveItem {
+        BulkRemoveItem::new(id, None)
+    }
+}
+
+impl From<String> for BulkRemoveItem {
+    fn from(id: String) -> BulkRemoveItem {
+        BulkRemoveItem { id, rev: None }
+    }
+}
+
+/// Error of a document that could not be fetched from `/{db}/_bulk_get`
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct BulkGetError {
+    pub id: String,
+    pub rev: Option<String>,
+    pub error: String,
+    pub reason: Option<String>
+}
+
+/// Outcome of the fetch of a revision: either the document or an error
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct BulkGetDoc {
+    pub ok: Option<Value>,
+    pub error: Option<BulkGetError>
+}
+
+/// Revisions fetched for one of the requested documents
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct BulkGetResult {
+    pub id: String,
+    pub docs: Vec<BulkGetDoc>
+}
+
+#[derive(Deserialize)]
+pub(crate) struct BulkGetResponse {
+    pub results: Vec<BulkGetResult>
+}
+
+/// Options of `/{db}/_bulk_docs`
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct BulkDocsParams {
+    /// When `false`, documents are stored with the `_rev` they carry instead
+    /// of getting a new revision, as replication does. No result is returned
+    /// for successful writes in this mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_edits: Option<bool>
+}
@@ -0,0 +1,230 @@
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::Seq;
+
+/// Feed mode of a changes-like endpoint (`_changes`, `_db_updates`)
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedMode {
+    /// Returns the changes that happened so far, right away
+    Normal,
+    /// Waits for at least one change before returning
+    Longpoll,
+    /// Streams changes as they happen, one JSON object per line
+    Continuous,
+    /// Streams changes as they happen, as server-sent events
+    EventSource
+}
+
+impl FeedMode {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            FeedMode::Normal => "normal",
+            FeedMode::Longpoll => "longpoll",
+            FeedMode::Continuous => "continuous",
+            FeedMode::EventSource => "eventsource"
+        }
+    }
+}
+
+/// Database event, as sent by `/_db_updates`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct DbUpdate {
+    pub db_name: String,
+    /// Kind of event: `created`, `updated` or `deleted`
+    #[serde(rename = "type")]
+    pub update_type: String,
+    pub seq: Option<Seq>
+}
+
+/// Batch of database events, as returned by `/_db_updates` in normal and
+/// longpoll modes
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct DbUpdates {
+    pub results: Vec<DbUpdate>,
+    pub last_seq: Option<Seq>
+}
+
+/// Parameters of `/_db_updates`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct DbUpdatesParams {
+    pub feed: Option<FeedMode>,
+    /// Time to wait for events before closing the feed, in milliseconds
+    pub timeout: Option<u64>,
+    /// Interval between two heartbeats sent to keep the feed alive, in
+    /// milliseconds
+    pub heartbeat: Option<u64>,
+    /// Only returns events that happened after this sequence. `now` skips
+    /// past events.
+    pub since: Option<Seq>
+}
+
+impl DbUpdatesParams {
+    pub fn to_args(&self) -> HashMap<String, String> {
+        let mut args = HashMap::new();
+
+        if let Some(feed) = self.feed {
+            args.insert(s!("feed"), s!(feed.as_str()));
+        }
+        if let Some(timeout) = self.timeout {
+            args.insert(s!("timeout"), timeout.to_string());
+        }
+        if let Some(heartbeat) = self.heartbeat {
+            args.insert(s!("heartbeat"), heartbeat.to_string());
+        }
+        if let Some(ref since) = self.since {
+            args.insert(s!("since"), since.to_string());
+        }
+
+        args
+    }
+}
+
+/// Revisions listed by a `_changes` feed: only the winning one, or all the
+/// leaves of the revision tree, conflicts included
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangesStyle {
+    MainOnly,
+    AllDocs
+}
+
+impl ChangesStyle {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ChangesStyle::MainOnly => "main_only",
+            ChangesStyle::AllDocs => "all_docs"
+        }
+    }
+}
+
+/// Filter of the documents whose changes are returned by `/{db}/_changes`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum ChangesFilter {
+    /// Filter function of a design document, as `ddoc/name`
+    Function(String),
+    /// Documents matching a Mango selector
+    Selector(Value),
+    /// Documents of a set of IDs
+    DocIds(Vec<String>),
+    /// Design documents
+    Design,
+    /// Documents emitted by the map function of a view, as `ddoc/view`
+    View(String)
+}
+
+impl ChangesFilter {
+    /// Value of the `filter` parameter
+    pub fn name(&self) -> &str {
+        match *self {
+            ChangesFilter::Function(ref name) => name,
+            ChangesFilter::Selector(_) => "_selector",
+            ChangesFilter::DocIds(_) => "_doc_ids",
+            ChangesFilter::Design => "_design",
+            ChangesFilter::View(_) => "_view"
+        }
+    }
+
+    /// Body the filter is sent along with, if any
+    pub fn body(&self) -> Option<Value> {
+        match *self {
+            ChangesFilter::Selector(ref selector) => Some(json!({ "selector": selector })),
+            ChangesFilter::DocIds(ref ids) => Some(json!({ "doc_ids": ids })),
+            _ => None
+        }
+    }
+}
+
+/// Parameters of `/{db}/_changes`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct ChangesParams {
+    pub filter: Option<ChangesFilter>,
+    /// Extra query parameters, passed to the filter function as `req.query`
+    #[serde(default)]
+    pub query_params: HashMap<String, String>,
+    pub feed: Option<FeedMode>,
+    /// Only returns changes that happened after this sequence. `now` skips
+    /// past changes.
+    pub since: Option<Seq>,
+    pub limit: Option<u64>,
+    pub descending: Option<bool>,
+    pub style: Option<ChangesStyle>,
+    /// Includes the changed documents in the results
+    pub include_docs: Option<bool>,
+    /// Time to wait for changes before closing a longpoll or continuous feed,
+    /// in milliseconds
+    pub timeout: Option<u64>,
+    /// Interval between two heartbeats sent to keep a longpoll or continuous
+    /// feed alive, in milliseconds
+    pub heartbeat: Option<u64>
+}
+
+impl ChangesParams {
+    pub fn to_args(&self) -> HashMap<String, String> {
+        let mut args = self.query_params.clone();
+
+        if let Some(ref filter) = self.filter {
+            args.insert(s!("filter"), s!(filter.name()));
+
+            if let ChangesFilter::View(ref view) = *filter {
+                args.insert(s!("view"), view.clone());
+            }
+        }
+        if let Some(feed) = self.feed {
+            args.insert(s!("feed"), s!(feed.as_str()));
+        }
+        if let Some(ref since) = self.since {
+            args.insert(s!("since"), since.to_string());
+        }
+        if let Some(limit) = self.limit {
+            args.insert(s!("limit"), limit.to_string());
+        }
+        if let Some(descending) = self.descending {
+            args.insert(s!("descending"), descending.to_string());
+        }
+        if let Some(style) = self.style {
+            args.insert(s!("style"), s!(style.as_str()));
+        }
+        if let Some(include_docs) = self.include_docs {
+            args.insert(s!("include_docs"), include_docs.to_string());
+        }
+        if let Some(timeout) = self.timeout {
+            args.insert(s!("timeout"), timeout.to_string());
+        }
+        if let Some(heartbeat) = self.heartbeat {
+            args.insert(s!("heartbeat"), heartbeat.to_string());
+        }
+
+        args
+    }
+}
+
+/// Revision of a document listed by a `_changes` feed
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ChangeRev {
+    pub rev: String
+}
+
+/// Change of a document, as sent by `/{db}/_changes`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ChangeEvent {
+    pub seq: Seq,
+    pub id: String,
+    pub changes: Vec<ChangeRev>,
+    #[serde(default)]
+    pub deleted: bool,
+    /// Document, when requested with `include_docs`
+    pub doc: Option<Value>
+}
+
+/// Batch of changes, as returned by `/{db}/_changes` in normal and longpoll
+/// modes
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Changes {
+    pub results: Vec<ChangeEvent>,
+    /// Sequence to resume the feed from
+    pub last_seq: Seq,
+    /// Number of changes left after the returned ones
+    pub pending: Option<u64>
+}
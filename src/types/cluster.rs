@@ -0,0 +1,58 @@
+/// Provisioning state of a node, as returned by `/_cluster_setup`
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ClusterSetupState {
+    ClusterDisabled,
+    SingleNodeDisabled,
+    SingleNodeEnabled,
+    ClusterEnabled,
+    ClusterFinished
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ClusterSetupStatus {
+    pub state: ClusterSetupState
+}
+
+/// Step of a cluster setup, sent to `/_cluster_setup`
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ClusterSetupAction {
+    /// Prepares the node, or the `remote_node` when set, to join a cluster
+    EnableCluster {
+        bind_address: String,
+        username: String,
+        password: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        port: Option<u16>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        node_count: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        remote_node: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        remote_current_user: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        remote_current_password: Option<String>
+    },
+    /// Sets the node up as a standalone server
+    EnableSingleNode {
+        bind_address: String,
+        username: String,
+        password: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        port: Option<u16>
+    },
+    /// Adds a node, previously enabled, to the cluster
+    AddNode {
+        host: String,
+        username: String,
+        password: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        port: Option<u16>
+    },
+    /// Creates the system databases once all nodes were added
+    FinishCluster {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        ensure_dbs_exist: Option<Vec<String>>
+    }
+}
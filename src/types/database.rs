@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+
+use super::Seq;
+
+/// Sizes of a database, in bytes
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct DbSizes {
+    /// Size of the live data inside the database
+    pub active: u64,
+    /// Size of the database files on disk
+    pub file: u64,
+    /// Uncompressed size of the database contents
+    pub external: u64
+}
+
+/// Options of the creation of a database
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct CreateDbOptions {
+    /// Creates a partitioned database, whose documents IDs are prefixed with
+    /// the partition they belong to
+    pub partitioned: Option<bool>,
+    /// Number of shards
+    pub q: Option<u32>,
+    /// Number of replicas of each shard
+    pub n: Option<u32>
+}
+
+impl CreateDbOptions {
+    pub fn to_args(&self) -> HashMap<String, String> {
+        let mut args = HashMap::new();
+
+        if let Some(partitioned) = self.partitioned {
+            args.insert(s!("partitioned"), partitioned.to_string());
+        }
+        if let Some(q) = self.q {
+            args.insert(s!("q"), q.to_string());
+        }
+        if let Some(n) = self.n {
+            args.insert(s!("n"), n.to_string());
+        }
+
+        args
+    }
+}
+
+/// Properties a database was created with
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct DbProps {
+    pub partitioned: Option<bool>
+}
+
+/// Clustering parameters of a database
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct DbClusterInfo {
+    /// Number of shards
+    pub q: u32,
+    /// Number of replicas of each shard
+    pub n: u32,
+    /// Write quorum
+    pub w: u32,
+    /// Read quorum
+    pub r: u32
+}
+
+/// Database information, as returned by `/{db}`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct DbInfo {
+    pub db_name: String,
+    /// Number of documents, deleted ones excluded
+    pub doc_count: u64,
+    /// Number of deleted documents
+    pub doc_del_count: u64,
+    /// Current sequence of the database
+    pub update_seq: Seq,
+    /// Current purge sequence of the database
+    pub purge_seq: Option<Seq>,
+    pub sizes: DbSizes,
+    #[serde(default)]
+    pub props: DbProps,
+    #[serde(default)]
+    pub compact_running: bool,
+    pub instance_start_time: Option<String>,
+    pub disk_format_version: Option<u32>,
+    pub cluster: Option<DbClusterInfo>
+}
+
+/// Information of one of the databases requested from `/_dbs_info`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct DbsInfoEntry {
+    /// Name of the database
+    pub key: String,
+    /// Information of the database, missing when it doesn't exist
+    pub info: Option<DbInfo>,
+    pub error: Option<String>
+}
+
+/// Users and roles granted a level of access to a database
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct SecurityGroup {
+    #[serde(default)]
+    pub names: Vec<String>,
+    #[serde(default)]
+    pub roles: Vec<String>
+}
+
+/// Security object of a database, as stored in `/{db}/_security`. A database
+/// without members is readable by everyone.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct SecurityDocument {
+    /// Users and roles that can manage the database and its design documents
+    #[serde(default)]
+    pub admins: SecurityGroup,
+    /// Users and roles that can read and write documents
+    #[serde(default)]
+    pub members: SecurityGroup
+}
+
+/// Result of a purge, as returned by `/{db}/_purge`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct PurgeResult {
+    /// Purge sequence of the database, `null` on clustered servers
+    pub purge_seq: Option<Seq>,
+    /// Revisions that were purged, by document ID
+    pub purged: HashMap<String, Vec<String>>
+}
+
+/// Revisions of a document missing from a database, as returned by
+/// `/{db}/_revs_diff`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct RevsDiff {
+    pub missing: Vec<String>,
+    /// Known revisions the missing ones may descend from
+    #[serde(default)]
+    pub possible_ancestors: Vec<String>
+}
+
+#[derive(Deserialize)]
+pub(crate) struct MissingRevs {
+    pub missing_revs: HashMap<String, Vec<String>>
+}
+
+/// Shards of a database, as returned by `/{db}/_shards`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct DbShards {
+    /// Nodes holding a copy of each shard, by range of document ID hashes
+    pub shards: HashMap<String, Vec<String>>
+}
+
+/// Shard holding a document, as returned by `/{db}/_shards/{docid}`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct DocumentShard {
+    pub range: String,
+    pub nodes: Vec<String>
+}
+
+/// Sizes of a partition, in bytes
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct PartitionSizes {
+    pub active: u64,
+    pub external: u64
+}
+
+/// Partition information, as returned by `/{db}/_partition/{partition}`
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct PartitionInfo {
+    pub db_name: String,
+    pub partition: String,
+    pub doc_count: u64,
+    pub doc_del_count: u64,
+    pub sizes: PartitionSizes
+}
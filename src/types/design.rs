@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use super::DESIGN_ID_PREFIX;
+
+/// Map and reduce functions of a view. `reduce` can also name a built-in
+/// reduce function, such as `_count` or `_sum`.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ViewFunction {
+    pub map: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reduce: Option<String>
+}
+
+impl ViewFunction {
+    pub fn new(map: &str, reduce: Option<&str>) -> ViewFunction {
+        ViewFunction {
+            map: s!(map),
+            reduce: reduce.map(|r| s!(r))
+        }
+    }
+}
+
+/// Options of a design document
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct DesignOptions {
+    /// Whether the views of the design document are partitioned, in a
+    /// partitioned database
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partitioned: Option<bool>
+}
+
+/// Design document, holding the views, filters and other functions of a
+/// database. Its functions are given as source code, in JavaScript unless
+/// `language` says otherwise. Fields this type doesn't model, such as
+/// `rewrites` or `autoupdate`, are kept in `extra`.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct DesignDocument {
+    /// Full ID, `_design/` prefix included
+    #[serde(rename = "_id")]
+    pub id: String,
+    #[serde(rename = "_rev", default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub views: HashMap<String, ViewFunction>,
+    /// Filter functions of changes feeds and replications
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub filters: HashMap<String, String>,
+    /// Update handlers
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub updates: HashMap<String, String>,
+    /// Show functions
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub shows: HashMap<String, String>,
+    /// List functions
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub lists: HashMap<String, String>,
+    /// Function run before every write, to reject invalid documents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validate_doc_update: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<DesignOptions>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>
+}
+
+impl DesignDocument {
+    /// Creates an empty design document, `name` being given with or without
+    /// its `_design/` prefix
+    pub fn new(name: &str) -> DesignDocument {
+        DesignDocument {
+            id: design_id(name),
+            ..Default::default()
+        }
+    }
+
+    /// Name of the design document, without its `_design/` prefix
+    pub fn name(&self) -> &str {
+        self.id.strip_prefix(DESIGN_ID_PREFIX).unwrap_or(&self.id)
+    }
+
+    pub fn language(mut self, language: &str) -> DesignDocument {
+        self.language = Some(s!(language));
+        self
+    }
+
+    pub fn view(mut self, name: &str, map: &str, reduce: Option<&str>) -> DesignDocument {
+        self.views.insert(s!(name), ViewFunction::new(map, reduce));
+        self
+    }
+
+    pub fn filter(mut self, name: &str, function: &str) -> DesignDocument {
+        self.filters.insert(s!(name), s!(function));
+        self
+    }
+
+    pub fn update(mut self, name: &str, function: &str) -> DesignDocument {
+        self.updates.insert(s!(name), s!(function));
+        self
+    }
+
+    pub fn show(mut self, name: &str, function: &str) -> DesignDocument {
+        self.shows.insert(s!(name), s!(function));
+        self
+    }
+
+    pub fn list(mut self, name: &str, function: &str) -> DesignDocument {
+        self.lists.insert(s!(name), s!(function));
+        self
+    }
+
+    pub fn validate_doc_update(mut self, function: &str) -> DesignDocument {
+        self.validate_doc_update = Some(s!(function));
+        self
+    }
+
+    pub fn partitioned(mut self, partitioned: bool) -> DesignDocument {
+        self.options = Some(DesignOptions {
+            partitioned: Some(partitioned)
+        });
+        self
+    }
+}
+
+/// Full ID of the design document `name`, given with or without its
+/// `_design/` prefix
+pub fn design_id(name: &str) -> String {
+    if name.starts_with(DESIGN_ID_PREFIX) {
+        s!(name)
+    } else {
+        format!("{}{}", DESIGN_ID_PREFIX, name)
+    }
+}
+
+/// Response of an update handler
+#[derive(PartialEq, Debug, Clone)]
+pub struct UpdateResponse {
+    /// Revision of the document saved by the handler, from the
+    /// `X-Couch-Update-NewRev` header. Missing when it saved nothing.
+    pub new_rev: Option<String>,
+    /// ID of the document saved by the handler, from the `X-Couch-Id` header
+    pub id: Option<String>,
+    pub content_type: Option<String>,
+    /// Body of the response, as returned by the handler
+    pub body: String
+}
+
+/// Response of a show or list function
+#[derive(PartialEq, Debug, Clone)]
+pub struct RenderedResponse {
+    pub content_type: Option<String>,
+    /// Body of the response, as rendered by the function
+    pub body: String
+}
@@ -1,5 +1,8 @@
-/// String that represents a Document ID in CouchDB
-pub type DocumentId = String;
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::{DocumentId, Rev, Seq};
 
 /// DocumentRef<T> is an abstraction over populated/unpopulated data fields
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
@@ -18,3 +21,273 @@ pub struct DocumentCreatedResult {
     pub error: Option<String>,
     pub reason: Option<String>
 }
+
+/// Current revision of a document listed by `_all_docs` and similar endpoints
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct DocumentRevision {
+    pub rev: String,
+    pub deleted: Option<bool>
+}
+
+/// Row of `_all_docs`, `_local_docs` or `_design_docs`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct AllDocsRow {
+    pub id: Option<String>,
+    pub key: Value,
+    pub value: Option<DocumentRevision>,
+    /// Document, when requested with `include_docs`
+    pub doc: Option<Value>,
+    /// Error of a requested key, such as `not_found`
+    pub error: Option<String>
+}
+
+/// Response of `_all_docs`, `_local_docs` or `_design_docs`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct AllDocsResponse {
+    pub total_rows: Option<u64>,
+    pub offset: Option<u64>,
+    pub rows: Vec<AllDocsRow>,
+    /// Sequence of the database the rows were read at, when requested with
+    /// `update_seq`
+    pub update_seq: Option<Seq>
+}
+
+/// Parameters of `/{db}/_all_docs`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct AllDocsParams {
+    /// Only returns the documents whose ID sorts after this one
+    pub start_key: Option<String>,
+    /// Only returns the documents whose ID sorts before this one
+    pub end_key: Option<String>,
+    /// Only returns the document with this ID
+    pub key: Option<String>,
+    pub descending: Option<bool>,
+    pub limit: Option<u64>,
+    pub skip: Option<u64>,
+    /// Includes the contents of the documents in the rows
+    pub include_docs: Option<bool>,
+    /// Includes the document whose ID is `end_key`, the default
+    pub inclusive_end: Option<bool>,
+    /// Includes the current sequence of the database in the response
+    pub update_seq: Option<bool>,
+    /// Includes the conflicting revisions of the documents, as `_conflicts`,
+    /// along with `include_docs`
+    pub conflicts: Option<bool>
+}
+
+impl AllDocsParams {
+    pub fn to_args(&self) -> HashMap<String, String> {
+        let mut args = HashMap::new();
+
+        if let Some(ref start_key) = self.start_key {
+            args.insert(s!("start_key"), js!(start_key));
+        }
+        if let Some(ref end_key) = self.end_key {
+            args.insert(s!("end_key"), js!(end_key));
+        }
+        if let Some(ref key) = self.key {
+            args.insert(s!("key"), js!(key));
+        }
+        if let Some(descending) = self.descending {
+            args.insert(s!("descending"), descending.to_string());
+        }
+        if let Some(limit) = self.limit {
+            args.insert(s!("limit"), limit.to_string());
+        }
+        if let Some(skip) = self.skip {
+            args.insert(s!("skip"), skip.to_string());
+        }
+        if let Some(include_docs) = self.include_docs {
+            args.insert(s!("include_docs"), include_docs.to_string());
+        }
+        if let Some(inclusive_end) = self.inclusive_end {
+            args.insert(s!("inclusive_end"), inclusive_end.to_string());
+        }
+        if let Some(update_seq) = self.update_seq {
+            args.insert(s!("update_seq"), update_seq.to_string());
+        }
+        if let Some(conflicts) = self.conflicts {
+            args.insert(s!("conflicts"), conflicts.to_string());
+        }
+
+        args
+    }
+}
+
+/// Parameters of `GET /{db}/{docid}`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct GetDocParams {
+    /// Fetches this revision instead of the current one
+    pub rev: Option<String>,
+    /// Includes the revision history of the document, as `_revisions`
+    pub revs: Option<bool>,
+    /// Includes the revisions of the document and their availability, as
+    /// `_revs_info`
+    pub revs_info: Option<bool>,
+    /// Includes the conflicting revisions of the document, as `_conflicts`
+    pub conflicts: Option<bool>,
+    /// Includes the deleted conflicting revisions, as `_deleted_conflicts`
+    pub deleted_conflicts: Option<bool>,
+    /// Fetches the latest leaf revision descending from `rev`
+    pub latest: Option<bool>,
+    /// Includes the contents of the attachments instead of stubs
+    pub attachments: Option<bool>,
+    /// Includes the compression of the attachments
+    pub att_encoding_info: Option<bool>
+}
+
+impl GetDocParams {
+    pub fn to_args(&self) -> HashMap<String, String> {
+        let mut args = HashMap::new();
+
+        if let Some(ref rev) = self.rev {
+            args.insert(s!("rev"), rev.clone());
+        }
+        if let Some(revs) = self.revs {
+            args.insert(s!("revs"), revs.to_string());
+        }
+        if let Some(revs_info) = self.revs_info {
+            args.insert(s!("revs_info"), revs_info.to_string());
+        }
+        if let Some(conflicts) = self.conflicts {
+            args.insert(s!("conflicts"), conflicts.to_string());
+        }
+        if let Some(deleted_conflicts) = self.deleted_conflicts {
+            args.insert(s!("deleted_conflicts"), deleted_conflicts.to_string());
+        }
+        if let Some(latest) = self.latest {
+            args.insert(s!("latest"), latest.to_string());
+        }
+        if let Some(attachments) = self.attachments {
+            args.insert(s!("attachments"), attachments.to_string());
+        }
+        if let Some(att_encoding_info) = self.att_encoding_info {
+            args.insert(s!("att_encoding_info"), att_encoding_info.to_string());
+        }
+
+        args
+    }
+}
+
+/// Revision history of a document, as included in `_revisions`
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct RevisionHistory {
+    /// Generation of the newest revision
+    pub start: u64,
+    /// Hashes of the revisions, newest first
+    pub ids: Vec<String>
+}
+
+impl RevisionHistory {
+    /// Full revisions of the history, newest first
+    pub fn revs(&self) -> Vec<Rev> {
+        self.ids
+            .iter()
+            .zip((1..=self.start).rev())
+            .filter_map(|(id, generation)| Rev::parse(&format!("{}-{}", generation, id)).ok())
+            .collect()
+    }
+}
+
+/// Revision of a document and its availability, as included in `_revs_info`
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct RevisionInfo {
+    pub rev: Rev,
+    /// `available`, `missing` or `deleted`
+    pub status: String
+}
+
+/// Revision metadata of a document, see `Document::revisions`
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct DocumentRevisions {
+    #[serde(rename = "_revisions")]
+    pub history: Option<RevisionHistory>,
+    #[serde(rename = "_revs_info", default)]
+    pub revs_info: Vec<RevisionInfo>,
+    #[serde(rename = "_conflicts", default)]
+    pub conflicts: Vec<Rev>,
+    #[serde(rename = "_deleted_conflicts", default)]
+    pub deleted_conflicts: Vec<Rev>
+}
+
+/// Document having conflicting revisions, see `Database::list_conflicts`
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct DocumentConflicts {
+    pub id: String,
+    /// Winning revision, the one read by default
+    pub rev: Rev,
+    /// Losing revisions
+    pub conflicts: Vec<Rev>
+}
+
+/// Leaf revision of a document, as returned with `open_revs`: its contents,
+/// or the revision when it's missing from the database
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct OpenRevision {
+    pub ok: Option<Value>,
+    pub missing: Option<Rev>
+}
+
+/// Result of a conditional request, sent with the revision the caller already
+/// knows of
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum Conditional<T> {
+    /// The resource changed since the known revision, or the revision wasn't
+    /// known
+    Modified(T),
+    /// The resource is still at the known revision
+    NotModified
+}
+
+impl<T> Conditional<T> {
+    /// Returns the fetched value, `None` when not modified
+    pub fn modified(self) -> Option<T> {
+        match self {
+            Conditional::Modified(value) => Some(value),
+            Conditional::NotModified => None
+        }
+    }
+}
+
+/// Document fetched by ID along with others, see `Database::get_bulk_typed`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum FetchedDoc<T> {
+    Found(T),
+    /// The document was deleted, at this revision
+    Deleted { rev: String },
+    /// The document never existed
+    Missing
+}
+
+impl<T> FetchedDoc<T> {
+    /// Returns the document, `None` when deleted or missing
+    pub fn found(self) -> Option<T> {
+        match self {
+            FetchedDoc::Found(doc) => Some(doc),
+            _ => None
+        }
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        matches!(*self, FetchedDoc::Deleted { .. })
+    }
+}
+
+/// Result of a write made with `batch=ok`
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum BatchWrite {
+    /// The document was written right away, at this revision
+    Stored { id: String, rev: String },
+    /// The document was accepted (`202 Accepted`) and will be written along
+    /// with other ones later on: it may be lost if the server goes down in
+    /// between, and its revision isn't known yet
+    Accepted { id: String }
+}
+
+impl BatchWrite {
+    pub fn id(&self) -> &str {
+        match *self {
+            BatchWrite::Stored { ref id, .. } | BatchWrite::Accepted { ref id } => id
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use serde_json::{Value};
 use std::collections::HashMap;
 
+use super::Index;
+
 /// Sort direction abstraction
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
 #[serde(untagged)]
@@ -13,7 +15,7 @@ impl From<String> for SortDirection {
     fn from(original: String) -> SortDirection {
         match original.as_ref() {
             "desc" => SortDirection::Desc,
-            "asc" | _ => SortDirection::Asc
+            _ => SortDirection::Asc
         }
     }
 }
@@ -37,35 +39,266 @@ pub enum IndexSpec {
     IndexName((String, String))
 }
 
-/// Find query abstraction
-#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+/// Find query abstraction, built fluently from its selector:
+///
+/// ```
+/// # #[macro_use] extern crate serde_json;
+/// # extern crate sofa;
+/// # use sofa::types::{FindQuery, SortSpec};
+/// # fn main() {
+/// let query = FindQuery::new(json!({ "type": "user" }))
+///     .fields(vec!["_id", "name"])
+///     .sort(SortSpec::Simple("name".into()))
+///     .limit(10);
+/// # }
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
 pub struct FindQuery {
     pub selector: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub skip: Option<u64>,
-    pub sort: Option<SortSpec>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sort: Vec<SortSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub fields: Option<Vec<String>>,
-    pub use_index: Option<IndexSpec>
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_index: Option<IndexSpec>,
+    /// Bookmark returned along a previous page of results, to fetch the next
+    /// one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bookmark: Option<String>,
+    /// Read quorum
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r: Option<u32>,
+    /// Includes the conflicting revisions of the documents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflicts: Option<bool>,
+    /// Reads from a stable set of shard replicas
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stable: Option<bool>,
+    /// Whether the index is brought up to date before being read
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update: Option<bool>,
+    /// Includes statistics about the execution of the query in the result
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_stats: Option<bool>
 }
 
-/// Find result abstraction
+impl FindQuery {
+    /// Starts a query from a selector, a `Selector` or raw JSON
+    pub fn new<S: Into<Value>>(selector: S) -> FindQuery {
+        FindQuery {
+            selector: selector.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn limit(mut self, limit: u64) -> FindQuery {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn skip(mut self, skip: u64) -> FindQuery {
+        self.skip = Some(skip);
+        self
+    }
+
+    /// Appends a sort criterion, criteria being applied in order
+    pub fn sort(mut self, sort: SortSpec) -> FindQuery {
+        self.sort.push(sort);
+        self
+    }
+
+    pub fn fields<S: Into<String>>(mut self, fields: Vec<S>) -> FindQuery {
+        self.fields = Some(fields.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn use_index(mut self, index: IndexSpec) -> FindQuery {
+        self.use_index = Some(index);
+        self
+    }
+
+    pub fn bookmark(mut self, bookmark: &str) -> FindQuery {
+        self.bookmark = Some(s!(bookmark));
+        self
+    }
+
+    pub fn r(mut self, r: u32) -> FindQuery {
+        self.r = Some(r);
+        self
+    }
+
+    pub fn conflicts(mut self, conflicts: bool) -> FindQuery {
+        self.conflicts = Some(conflicts);
+        self
+    }
+
+    pub fn stable(mut self, stable: bool) -> FindQuery {
+        self.stable = Some(stable);
+        self
+    }
+
+    pub fn update(mut self, update: bool) -> FindQuery {
+        self.update = Some(update);
+        self
+    }
+
+    pub fn execution_stats(mut self, execution_stats: bool) -> FindQuery {
+        self.execution_stats = Some(execution_stats);
+        self
+    }
+
+    /// Fields an index has to hold, in order, to answer the query: the
+    /// fields the selector matches exact values of, then the ones it matches
+    /// ranges of, then the sort fields
+    pub fn index_fields(&self) -> Vec<String> {
+        let mut equalities = Vec::new();
+        let mut ranges = Vec::new();
+        collect_selector_fields(&self.selector, "", &mut equalities, &mut ranges);
+
+        let sorts = self.sort.iter().filter_map(|sort| match *sort {
+            SortSpec::Simple(ref field) => Some(field.clone()),
+            SortSpec::Complex(ref spec) => spec.keys().next().cloned(),
+        });
+
+        let mut fields: Vec<String> = Vec::new();
+        for field in equalities.into_iter().chain(ranges).chain(sorts) {
+            if !fields.contains(&field) {
+                fields.push(field);
+            }
+        }
+
+        fields
+    }
+}
+
+/// Sorts the fields `selector` conditions into the ones matched against an
+/// exact value and the other ones, going through `$and` combinations and
+/// nested objects. Fields under other combinations can't be answered by a
+/// single index and are left out.
+fn collect_selector_fields(selector: &Value, prefix: &str, equalities: &mut Vec<String>, ranges: &mut Vec<String>) {
+    let conditions = match *selector {
+        Value::Object(ref conditions) => conditions,
+        _ => return,
+    };
+
+    for (key, condition) in conditions {
+        if key == "$and" {
+            for selector in condition.as_array().into_iter().flatten() {
+                collect_selector_fields(selector, prefix, equalities, ranges);
+            }
+            continue;
+        }
+        if key.starts_with('$') {
+            continue;
+        }
+
+        let field = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match *condition {
+            Value::Object(ref operators) if operators.keys().all(|op| op.starts_with('$')) => {
+                if operators.len() == 1 && operators.contains_key("$eq") {
+                    equalities.push(field);
+                } else if !operators.is_empty() {
+                    ranges.push(field);
+                }
+            }
+            Value::Object(_) => collect_selector_fields(condition, &field, equalities, ranges),
+            _ => equalities.push(field),
+        }
+    }
+}
+
+/// Statistics about the execution of a Mango query, returned when requested
+/// with `execution_stats`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ExecutionStats {
+    /// Number of index keys read
+    pub total_keys_examined: u64,
+    /// Number of documents read, high counts compared to `results_returned`
+    /// hinting at a missing index
+    pub total_docs_examined: u64,
+    /// Number of documents read with a read quorum
+    pub total_quorum_docs_examined: u64,
+    pub results_returned: u64,
+    pub execution_time_ms: f64
+}
+
+/// Find result abstraction, documents being raw JSON values unless
+/// deserialized into `T` through `Database::find_typed`
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
-pub struct FindResult {
-    pub docs: Option<Vec<Value>>,
+pub struct FindResult<T = Value> {
+    pub docs: Option<Vec<T>>,
+    /// Bookmark to fetch the next page of results from
+    pub bookmark: Option<String>,
+    /// Warning about the query, e.g. when no index could answer it
     pub warning: Option<String>,
+    pub execution_stats: Option<ExecutionStats>,
     pub error: Option<String>,
     pub reason: Option<String>
 }
 
-/// Explain result abstraction
+/// Reason an index was or wasn't selected for a query, as reported by
+/// `_explain` in CouchDB 3.3 and later
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct IndexCandidateReason {
+    pub name: String
+}
+
+/// Analysis of an index considered for a query
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct IndexAnalysis {
+    /// Whether the index can answer the query at all
+    pub usable: bool,
+    #[serde(default)]
+    pub reasons: Vec<IndexCandidateReason>,
+    /// Rank of the index among the usable ones, 1 being the best
+    pub ranking: Option<u32>,
+    /// Whether the index holds all the fields the query returns
+    pub covering: Option<bool>
+}
+
+/// Index considered for a query, but not chosen
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct IndexCandidate {
+    pub index: Index,
+    pub analysis: IndexAnalysis
+}
+
+/// Explain result abstraction, as returned by `/{db}/_explain`
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct ExplainResult {
     pub dbname: String,
-    pub index: IndexSpec,
+    /// Index chosen to answer the query
+    pub index: Index,
+    #[serde(default)]
+    pub partitioned: Value,
     pub selector: Value,
     pub opts: Value,
-    pub limit: u32,
+    pub limit: u64,
     pub skip: u64,
-    pub fields: Vec<String>,
-    pub range: Value
+    /// Fields returned by the query, `"all_fields"` when not restricted
+    pub fields: Value,
+    /// Range of the chosen index being read
+    pub range: Option<Value>,
+    /// Whether the chosen index holds all the fields the query returns, so
+    /// that documents don't need to be read
+    pub covering: Option<bool>,
+    /// Other indexes considered, and why they weren't chosen
+    #[serde(default)]
+    pub index_candidates: Vec<IndexCandidate>,
+    /// Analysis of the selector, e.g. the fields an index could be created
+    /// on
+    #[serde(default)]
+    pub selector_hints: Vec<Value>
+}
+
+impl ExplainResult {
+    /// Whether the query has to scan all the documents of the database,
+    /// because no index can answer it
+    pub fn is_full_scan(&self) -> bool {
+        self.index.index_type == "special"
+    }
 }
@@ -0,0 +1,13 @@
+/// IAM token exchange result abstraction
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct IamToken {
+    pub access_token: Option<String>,
+    pub refresh_token: Option<String>,
+    pub token_type: Option<String>,
+    pub expires_in: Option<u64>,
+    pub expiration: Option<u64>,
+    #[serde(rename = "errorCode")]
+    pub error_code: Option<String>,
+    #[serde(rename = "errorMessage")]
+    pub error_message: Option<String>
+}
@@ -0,0 +1,237 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+use error::SofaError;
+
+/// Prefix of the IDs of design documents
+pub const DESIGN_ID_PREFIX: &str = "_design/";
+
+/// Prefix of the IDs of local documents, which are never replicated
+pub const LOCAL_ID_PREFIX: &str = "_local/";
+
+/// ID of a document in CouchDB. It derefs to `str`, and can be built from
+/// any string through `From`, or through `DocumentId::parse` to have it
+/// validated first.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Clone, Default)]
+#[serde(transparent)]
+pub struct DocumentId(String);
+
+impl DocumentId {
+    /// Validates an ID: it must not be empty, and only design and local
+    /// documents may start with an underscore
+    pub fn parse(id: &str) -> Result<DocumentId, SofaError> {
+        if id.is_empty() {
+            return Err(SofaError::Custom(s!("document ID is empty")));
+        }
+
+        if id.starts_with('_') && !id.starts_with(DESIGN_ID_PREFIX) && !id.starts_with(LOCAL_ID_PREFIX) {
+            return Err(SofaError::Custom(format!("document ID {} is reserved", id)));
+        }
+
+        Ok(DocumentId(s!(id)))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// Whether this is the ID of a design document
+    pub fn is_design(&self) -> bool {
+        self.0.starts_with(DESIGN_ID_PREFIX)
+    }
+
+    /// Whether this is the ID of a local document, which is never replicated
+    pub fn is_local(&self) -> bool {
+        self.0.starts_with(LOCAL_ID_PREFIX)
+    }
+}
+
+impl Deref for DocumentId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for DocumentId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for DocumentId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for DocumentId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for DocumentId {
+    type Err = SofaError;
+
+    fn from_str(id: &str) -> Result<DocumentId, SofaError> {
+        DocumentId::parse(id)
+    }
+}
+
+impl From<String> for DocumentId {
+    fn from(id: String) -> DocumentId {
+        DocumentId(id)
+    }
+}
+
+impl<'a> From<&'a str> for DocumentId {
+    fn from(id: &'a str) -> DocumentId {
+        DocumentId(s!(id))
+    }
+}
+
+impl<'a> From<&'a String> for DocumentId {
+    fn from(id: &'a String) -> DocumentId {
+        DocumentId(id.clone())
+    }
+}
+
+impl<'a> From<&'a DocumentId> for DocumentId {
+    fn from(id: &'a DocumentId) -> DocumentId {
+        id.clone()
+    }
+}
+
+impl From<DocumentId> for String {
+    fn from(id: DocumentId) -> String {
+        id.0
+    }
+}
+
+impl PartialEq<str> for DocumentId {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for DocumentId {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for DocumentId {
+    fn eq(&self, other: &String) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<DocumentId> for str {
+    fn eq(&self, other: &DocumentId) -> bool {
+        self == other.0
+    }
+}
+
+impl PartialEq<DocumentId> for &str {
+    fn eq(&self, other: &DocumentId) -> bool {
+        *self == other.0
+    }
+}
+
+impl PartialEq<DocumentId> for String {
+    fn eq(&self, other: &DocumentId) -> bool {
+        *self == other.0
+    }
+}
+
+/// Revision of a document, made of a generation number counting its
+/// updates and of a hash of its contents, as in `3-917fa23...`. Revisions
+/// are ordered by generation, then by hash, which is how CouchDB picks the
+/// winning revision among conflicting ones.
+#[derive(Eq, PartialEq, Hash, Debug, Clone)]
+pub struct Rev {
+    generation: u64,
+    hash: String,
+}
+
+impl Rev {
+    /// Parses and validates a revision
+    pub fn parse(rev: &str) -> Result<Rev, SofaError> {
+        let invalid = || SofaError::Custom(format!("invalid revision {}", rev));
+        let mut parts = rev.splitn(2, '-');
+
+        let generation: u64 = parts.next().and_then(|n| n.parse().ok()).ok_or_else(invalid)?;
+        let hash = parts.next().ok_or_else(invalid)?;
+
+        if generation == 0 || hash.is_empty() || !hash.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(invalid());
+        }
+
+        Ok(Rev {
+            generation,
+            hash: s!(hash),
+        })
+    }
+
+    /// Number of updates the document went through up to this revision
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+}
+
+impl Ord for Rev {
+    fn cmp(&self, other: &Rev) -> Ordering {
+        self.generation
+            .cmp(&other.generation)
+            .then_with(|| self.hash.cmp(&other.hash))
+    }
+}
+
+impl PartialOrd for Rev {
+    fn partial_cmp(&self, other: &Rev) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rev {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.generation, self.hash)
+    }
+}
+
+impl FromStr for Rev {
+    type Err = SofaError;
+
+    fn from_str(rev: &str) -> Result<Rev, SofaError> {
+        Rev::parse(rev)
+    }
+}
+
+impl Serialize for Rev {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rev {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Rev, D::Error> {
+        let rev = String::deserialize(deserializer)?;
+        Rev::parse(&rev).map_err(de::Error::custom)
+    }
+}
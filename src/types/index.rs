@@ -1,19 +1,95 @@
+use serde_json::Value;
+
 use super::*;
 
 /// Index fields abstraction
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
 pub struct IndexFields {
-    pub fields: Vec<SortSpec>
+    pub fields: Vec<SortSpec>,
+    /// Selector documents have to match to be indexed, see
+    /// `partial_filter_selector`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partial_filter_selector: Option<Value>
 }
 
 impl IndexFields {
     pub fn new(fields: Vec<SortSpec>) -> IndexFields {
         IndexFields {
-            fields: fields
+            fields,
+            partial_filter_selector: None
+        }
+    }
+
+    /// Only indexes the documents matching `selector`, e.g.
+    /// `{"deleted": {"$ne": true}}` to leave soft-deleted documents out. Only
+    /// queries whose selector implies the partial filter can use the index,
+    /// and they have to name it through `use_index`.
+    pub fn partial_filter_selector(mut self, selector: Value) -> IndexFields {
+        self.partial_filter_selector = Some(selector);
+        self
+    }
+}
+
+/// Type of a field indexed by a text index
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum TextFieldType {
+    Boolean,
+    Number,
+    String
+}
+
+/// Field indexed by a text index
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct TextIndexField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: TextFieldType
+}
+
+impl TextIndexField {
+    pub fn new(name: &str, field_type: TextFieldType) -> TextIndexField {
+        TextIndexField {
+            name: s!(name),
+            field_type
         }
     }
 }
 
+/// Text index fields abstraction, for deployments running full-text search.
+/// Without fields, all the fields of the documents are indexed.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct TextIndexFields {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<TextIndexField>,
+    /// Analyzer of the indexed text, `standard` by default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_analyzer: Option<String>,
+    /// Settings of the `default` field, searched by `$text` queries, e.g.
+    /// `{"enabled": true, "analyzer": "english"}`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_field: Option<Value>,
+    /// Indexes the lengths of array fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index_array_lengths: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partial_filter_selector: Option<Value>
+}
+
+impl TextIndexFields {
+    pub fn new(fields: Vec<TextIndexField>) -> TextIndexFields {
+        TextIndexFields {
+            fields,
+            ..Default::default()
+        }
+    }
+
+    pub fn default_analyzer(mut self, analyzer: &str) -> TextIndexFields {
+        self.default_analyzer = Some(s!(analyzer));
+        self
+    }
+}
+
 /// Index abstraction
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
 pub struct Index {
@@ -1,4 +1,22 @@
 mod_use!(system);
+mod_use!(database);
+mod_use!(id);
 mod_use!(document);
+mod_use!(design);
+mod_use!(view);
+mod_use!(search);
+mod_use!(response);
+mod_use!(attachment);
+mod_use!(bulk);
 mod_use!(find);
+mod_use!(selector);
 mod_use!(index);
+mod_use!(seq);
+mod_use!(changes);
+mod_use!(node);
+mod_use!(cluster);
+mod_use!(reshard);
+mod_use!(replication);
+mod_use!(user);
+#[cfg(feature = "cloudant")]
+mod_use!(iam);
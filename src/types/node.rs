@@ -0,0 +1,84 @@
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Statistic of a node, as returned by `/_node/{node}/_stats`: either a
+/// metric, or a group of statistics indexed by name
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(untagged)]
+pub enum NodeStat {
+    Metric(StatMetric),
+    Group(HashMap<String, NodeStat>)
+}
+
+impl NodeStat {
+    /// Looks up the metric at `path`, e.g. `&["couchdb", "open_databases"]`
+    pub fn get(&self, path: &[&str]) -> Option<&StatMetric> {
+        match (self, path.split_first()) {
+            (NodeStat::Metric(metric), None) => Some(metric),
+            (NodeStat::Group(group), Some((name, rest))) => group.get(*name).and_then(|stat| stat.get(rest)),
+            _ => None
+        }
+    }
+}
+
+/// Metric of a node
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct StatMetric {
+    pub value: StatValue,
+    /// Kind of metric: `counter`, `gauge` or `histogram`
+    #[serde(rename = "type")]
+    pub metric_type: String,
+    pub desc: Option<String>
+}
+
+/// Value of a metric, a single number for counters and gauges
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(untagged)]
+pub enum StatValue {
+    Number(f64),
+    Histogram(StatHistogram)
+}
+
+/// Summary of the samples of a histogram metric
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct StatHistogram {
+    pub n: u64,
+    pub min: f64,
+    pub max: f64,
+    pub arithmetic_mean: f64,
+    pub geometric_mean: f64,
+    pub harmonic_mean: f64,
+    pub median: f64,
+    pub variance: f64,
+    pub standard_deviation: f64,
+    pub skewness: f64,
+    pub kurtosis: f64,
+    /// `(percentile, value)` pairs
+    pub percentile: Vec<(f64, f64)>,
+    /// `(bucket, count)` pairs
+    pub histogram: Vec<(f64, f64)>
+}
+
+/// Erlang VM figures of a node, as returned by `/_node/{node}/_system`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct NodeSystem {
+    /// Uptime, in seconds
+    pub uptime: u64,
+    /// Memory used, in bytes, by kind (`processes`, `binary`, `ets`...)
+    pub memory: HashMap<String, u64>,
+    pub run_queue: Option<u64>,
+    pub ets_table_count: Option<u64>,
+    pub context_switches: Option<u64>,
+    pub reductions: Option<u64>,
+    pub garbage_collection_count: Option<u64>,
+    pub words_reclaimed: Option<u64>,
+    pub io_input: Option<u64>,
+    pub io_output: Option<u64>,
+    pub os_proc_count: Option<u64>,
+    pub stale_proc_count: Option<u64>,
+    pub process_count: Option<u64>,
+    pub process_limit: Option<u64>,
+    pub message_queues: Option<HashMap<String, Value>>,
+    pub internal_replication_jobs: Option<u64>,
+    pub distribution: Option<HashMap<String, Value>>
+}
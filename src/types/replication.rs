@@ -0,0 +1,191 @@
+use serde_json::Value;
+
+use super::Seq;
+
+/// State of a replication, as tracked by the replication scheduler
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplicationState {
+    Initializing,
+    Running,
+    Pending,
+    Crashing,
+    Error,
+    Failed,
+    Completed,
+    #[serde(other)]
+    Unknown
+}
+
+/// Event of the history of a replication job
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct SchedulerJobEvent {
+    pub timestamp: String,
+    /// Kind of event: `added`, `started`, `crashed`...
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub reason: Option<String>
+}
+
+/// Replication job run by the scheduler, as listed by `/_scheduler/jobs`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct SchedulerJob {
+    pub id: String,
+    /// Replicator database holding the replication document, if any
+    pub database: Option<String>,
+    pub doc_id: Option<String>,
+    pub pid: Option<String>,
+    pub node: Option<String>,
+    pub source: String,
+    pub target: String,
+    pub user: Option<String>,
+    pub start_time: Option<String>,
+    pub history: Vec<SchedulerJobEvent>,
+    pub info: Option<Value>
+}
+
+/// Replication jobs list, as returned by `/_scheduler/jobs`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct SchedulerJobs {
+    pub jobs: Vec<SchedulerJob>,
+    pub offset: u64,
+    pub total_rows: u64
+}
+
+/// State of a replication document, as listed by `/_scheduler/docs`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct SchedulerDoc {
+    pub database: String,
+    pub doc_id: String,
+    /// Replication ID, once the replication was started
+    pub id: Option<String>,
+    pub node: Option<String>,
+    pub source: Option<String>,
+    pub target: Option<String>,
+    pub state: ReplicationState,
+    /// Replication statistics, or the last error when the state is `crashing`
+    /// or `failed`
+    pub info: Option<Value>,
+    pub error_count: u64,
+    pub last_updated: Option<String>,
+    pub start_time: Option<String>,
+    pub source_proxy: Option<String>,
+    pub target_proxy: Option<String>
+}
+
+/// Replication documents list, as returned by `/_scheduler/docs`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct SchedulerDocs {
+    pub docs: Vec<SchedulerDoc>,
+    pub offset: u64,
+    pub total_rows: u64
+}
+
+/// Options of a replication, the ones left unset taking CouchDB's defaults
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct ReplicationOptions {
+    /// Creates the target database if it doesn't exist
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub create_target: Option<bool>,
+    /// Keeps replicating the changes made to the source
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuous: Option<bool>,
+    /// Cancels the identical replication running
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancel: Option<bool>,
+    /// Filter function of the source, as `ddoc/filter`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    /// Parameters passed to the filter function
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_params: Option<Value>,
+    /// Only replicates these documents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_ids: Option<Vec<String>>,
+    /// Only replicates the documents matching this Mango selector
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<Value>,
+    /// Starts replicating from this source sequence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since_seq: Option<Seq>,
+    /// Proxy through which the source and target are reached
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>
+}
+
+/// Session of a replication, as recorded in its history
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ReplicationHistory {
+    pub session_id: String,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub start_last_seq: Option<Seq>,
+    pub end_last_seq: Option<Seq>,
+    pub recorded_seq: Option<Seq>,
+    pub missing_checked: Option<u64>,
+    pub missing_found: Option<u64>,
+    pub docs_read: Option<u64>,
+    pub docs_written: Option<u64>,
+    pub doc_write_failures: Option<u64>
+}
+
+/// Outcome of a replication, as returned by `/_replicate`. Continuous
+/// replications only get their `_local_id` back.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ReplicationResult {
+    pub ok: Option<bool>,
+    pub session_id: Option<String>,
+    pub source_last_seq: Option<Seq>,
+    pub replication_id_version: Option<u64>,
+    #[serde(rename = "_local_id")]
+    pub local_id: Option<String>,
+    #[serde(default)]
+    pub history: Vec<ReplicationHistory>,
+    pub no_changes: Option<bool>,
+    pub error: Option<String>,
+    pub reason: Option<String>
+}
+
+/// Persistent replication, as stored in a `_replicator` database. The
+/// `replication_*` fields are maintained by the server and never written
+/// back.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ReplicationDocument {
+    #[serde(rename = "_id")]
+    pub id: String,
+    #[serde(rename = "_rev", skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+    /// Database name or URL, or an object holding the URL and headers
+    pub source: Value,
+    /// Database name or URL, or an object holding the URL and headers
+    pub target: Value,
+    #[serde(flatten)]
+    pub options: ReplicationOptions,
+    #[serde(rename = "_replication_state", skip_serializing)]
+    pub replication_state: Option<ReplicationState>,
+    #[serde(rename = "_replication_state_time", skip_serializing)]
+    pub replication_state_time: Option<String>,
+    #[serde(rename = "_replication_state_reason", skip_serializing)]
+    pub replication_state_reason: Option<String>,
+    #[serde(rename = "_replication_id", skip_serializing)]
+    pub replication_id: Option<String>,
+    #[serde(rename = "_replication_stats", skip_serializing)]
+    pub replication_stats: Option<Value>
+}
+
+impl ReplicationDocument {
+    pub fn new(id: String, source: &str, target: &str, options: ReplicationOptions) -> ReplicationDocument {
+        ReplicationDocument {
+            id,
+            rev: None,
+            source: json!(source),
+            target: json!(target),
+            options,
+            replication_state: None,
+            replication_state_time: None,
+            replication_state_reason: None,
+            replication_id: None,
+            replication_stats: None
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use serde_json::Value;
+
+/// Target state of resharding, globally or for a job
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ReshardRunState {
+    Running,
+    Stopped
+}
+
+/// Resharding summary, as returned by `/_reshard`
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ReshardSummary {
+    pub state: String,
+    pub state_reason: Option<String>,
+    pub completed: u64,
+    pub failed: u64,
+    pub running: u64,
+    pub stopped: u64,
+    pub total: u64
+}
+
+/// Resharding state, globally or of a job
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ReshardState {
+    pub state: String,
+    pub reason: Option<String>
+}
+
+/// Event of the history of a resharding job
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ReshardHistoryEvent {
+    pub detail: Option<String>,
+    pub timestamp: String,
+    #[serde(rename = "type")]
+    pub event_type: String
+}
+
+/// Resharding job
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ReshardJob {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub job_type: String,
+    pub job_state: String,
+    pub split_state: Option<String>,
+    pub state_info: Option<Value>,
+    pub node: String,
+    pub source: String,
+    pub target: Vec<String>,
+    pub start_time: Option<String>,
+    pub update_time: Option<String>,
+    pub history: Vec<ReshardHistoryEvent>
+}
+
+/// Resharding jobs list, as returned by `/_reshard/jobs`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ReshardJobs {
+    pub jobs: Vec<ReshardJob>,
+    pub offset: u64,
+    pub total_rows: u64
+}
+
+/// Resharding jobs creation request. Either `shard`, or `db` optionally
+/// narrowed to a `node` and a `range`, selects the shards to split.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ReshardJobRequest {
+    #[serde(rename = "type")]
+    pub job_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub db: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shard: Option<String>
+}
+
+impl ReshardJobRequest {
+    /// Splits all the shards of a database
+    pub fn split_db(db: String) -> ReshardJobRequest {
+        ReshardJobRequest {
+            job_type: s!("split"),
+            db: Some(db),
+            node: None,
+            range: None,
+            shard: None
+        }
+    }
+
+    /// Splits a single shard, e.g. `shards/00000000-1fffffff/db.1549492084`
+    pub fn split_shard(shard: String) -> ReshardJobRequest {
+        ReshardJobRequest {
+            job_type: s!("split"),
+            db: None,
+            node: None,
+            range: None,
+            shard: Some(shard)
+        }
+    }
+}
+
+/// Outcome of the creation of a resharding job
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct ReshardJobCreated {
+    pub ok: Option<bool>,
+    pub id: Option<String>,
+    pub node: Option<String>,
+    pub shard: Option<String>,
+    pub error: Option<String>,
+    pub reason: Option<String>
+}
@@ -0,0 +1,29 @@
+/// Headers of a CouchDB response worth keeping along its result, to
+/// correlate a call with the server logs
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Default)]
+pub struct ResponseMeta {
+    /// `X-Couch-Request-ID`, the ID the server logged the request under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// `X-CouchDB-Body-Time`, the time the server spent reading the request
+    /// body, in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_time: Option<u64>,
+    /// `ETag`, without its quotes: the revision of a fetched document, or the
+    /// signature of a view result
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>
+}
+
+/// Result of a call along with the metadata of its response
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct WithMeta<T> {
+    pub value: T,
+    pub meta: ResponseMeta
+}
+
+impl<T> WithMeta<T> {
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
@@ -0,0 +1,163 @@
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::DocumentId;
+
+/// Full-text search query, run against a search index of a design document,
+/// built fluently from its Lucene query:
+///
+/// ```
+/// # extern crate sofa;
+/// # use sofa::types::SearchQuery;
+/// # fn main() {
+/// let query = SearchQuery::new("color:red")
+///     .sort(vec!["-seats<number>"])
+///     .counts(vec!["color"])
+///     .highlight_fields(vec!["name"])
+///     .limit(10);
+/// # }
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct SearchQuery {
+    /// Lucene query
+    #[serde(rename = "q")]
+    pub query: String,
+    /// Fields to sort the results by, prefixed with `-` for a descending
+    /// order and suffixed with their type, as in `-seats<number>`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sort: Vec<String>,
+    /// Bookmark returned along a previous page of results, to fetch the next
+    /// one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bookmark: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    /// Includes the documents that matched in the rows
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_docs: Option<bool>,
+    /// Fields to count the distinct values of among the results
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counts: Option<Vec<String>>,
+    /// Ranges to count the results within, by field, as in
+    /// `{ "seats": { "few": "[0 TO 2]", "many": "{2 TO Infinity]" } }`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ranges: Option<Value>,
+    /// Restricts the results to the ones whose field has a value, as field
+    /// and value pairs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub drilldown: Vec<(String, String)>,
+    /// Fields to highlight the matches of
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight_fields: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight_pre_tag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight_post_tag: Option<String>,
+    /// Number of fragments returned per highlighted field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight_number: Option<u64>,
+    /// Number of characters of each highlighted fragment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight_size: Option<u64>
+}
+
+impl SearchQuery {
+    pub fn new(query: &str) -> SearchQuery {
+        SearchQuery {
+            query: s!(query),
+            ..Default::default()
+        }
+    }
+
+    pub fn sort<S: Into<String>>(mut self, sort: Vec<S>) -> SearchQuery {
+        self.sort = sort.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn bookmark(mut self, bookmark: &str) -> SearchQuery {
+        self.bookmark = Some(s!(bookmark));
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> SearchQuery {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn include_docs(mut self, include_docs: bool) -> SearchQuery {
+        self.include_docs = Some(include_docs);
+        self
+    }
+
+    pub fn counts<S: Into<String>>(mut self, fields: Vec<S>) -> SearchQuery {
+        self.counts = Some(fields.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn ranges(mut self, ranges: Value) -> SearchQuery {
+        self.ranges = Some(ranges);
+        self
+    }
+
+    /// Appends a drilldown, drilldowns being combined with a logical AND
+    pub fn drilldown(mut self, field: &str, value: &str) -> SearchQuery {
+        self.drilldown.push((s!(field), s!(value)));
+        self
+    }
+
+    pub fn highlight_fields<S: Into<String>>(mut self, fields: Vec<S>) -> SearchQuery {
+        self.highlight_fields = Some(fields.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Tags wrapped around the highlighted matches, `<em>` and `</em>` by
+    /// default
+    pub fn highlight_tags(mut self, pre_tag: &str, post_tag: &str) -> SearchQuery {
+        self.highlight_pre_tag = Some(s!(pre_tag));
+        self.highlight_post_tag = Some(s!(post_tag));
+        self
+    }
+
+    pub fn highlight_number(mut self, number: u64) -> SearchQuery {
+        self.highlight_number = Some(number);
+        self
+    }
+
+    pub fn highlight_size(mut self, size: u64) -> SearchQuery {
+        self.highlight_size = Some(size);
+        self
+    }
+}
+
+/// Result of a search query, its rows holding the stored fields of the
+/// index as `F`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct SearchResponse<F> {
+    /// Number of results, over all pages
+    pub total_rows: u64,
+    /// Bookmark to give to the next query, to fetch the next page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bookmark: Option<String>,
+    pub rows: Vec<SearchRow<F>>,
+    /// Number of results per distinct value, by counted field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counts: Option<HashMap<String, HashMap<String, u64>>>,
+    /// Number of results per range, by field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ranges: Option<HashMap<String, HashMap<String, u64>>>
+}
+
+/// Document that matched a search query
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct SearchRow<F> {
+    pub id: DocumentId,
+    /// Sort order of the row, its relevance first unless sorted otherwise
+    #[serde(default)]
+    pub order: Vec<Value>,
+    pub fields: F,
+    /// Highlighted fragments of the matches, by field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlights: Option<HashMap<String, Vec<String>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<Value>
+}
@@ -0,0 +1,245 @@
+use std::ops::Not;
+
+use serde_json::{Map, Value};
+
+/// Mango selector, built from conditions on fields combined together:
+///
+/// ```
+/// # extern crate sofa;
+/// # use sofa::types::Selector;
+/// # fn main() {
+/// let selector = Selector::field("age").gt(21)
+///     .and(Selector::field("type").eq("user"))
+///     .and(!Selector::field("banned").eq(true));
+/// # }
+/// ```
+///
+/// Selectors are serialized as the JSON the server expects, and convert into
+/// a `Value` to be passed wherever raw selectors are accepted.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(transparent)]
+pub struct Selector(Value);
+
+impl Selector {
+    /// Starts a condition on a field, nested fields being separated by dots
+    pub fn field(name: &str) -> Field {
+        Field { name: Some(s!(name)) }
+    }
+
+    /// Starts a condition on the elements of an array, to be used within
+    /// `Field::elem_match` and `Field::all_match`
+    pub fn element() -> Field {
+        Field { name: None }
+    }
+
+    /// Selector matching every document
+    pub fn all() -> Selector {
+        Selector(json!({}))
+    }
+
+    /// Matches the documents matching both selectors
+    pub fn and(self, other: Selector) -> Selector {
+        self.combine("$and", other)
+    }
+
+    /// Matches the documents matching any of the selectors
+    pub fn or(self, other: Selector) -> Selector {
+        self.combine("$or", other)
+    }
+
+    /// Matches the documents matching none of the selectors
+    pub fn nor(selectors: Vec<Selector>) -> Selector {
+        let selectors: Vec<Value> = selectors.into_iter().map(|s| s.0).collect();
+        Selector(json!({ "$nor": selectors }))
+    }
+
+    /// Chains conditions combined with the same operator into a single list
+    fn combine(self, operator: &str, other: Selector) -> Selector {
+        let mut selectors = match self.0 {
+            Value::Object(mut map) => {
+                if map.len() == 1 && map.get(operator).is_some_and(Value::is_array) {
+                    match map.remove(operator) {
+                        Some(Value::Array(selectors)) => selectors,
+                        _ => unreachable!(),
+                    }
+                } else {
+                    vec![Value::Object(map)]
+                }
+            }
+            value => vec![value],
+        };
+        selectors.push(other.0);
+
+        let mut map = Map::new();
+        map.insert(s!(operator), Value::Array(selectors));
+        Selector(Value::Object(map))
+    }
+
+    /// Returns the JSON form of the selector
+    pub fn as_value(&self) -> &Value {
+        &self.0
+    }
+}
+
+/// Matches the documents not matching the selector
+impl Not for Selector {
+    type Output = Selector;
+
+    fn not(self) -> Selector {
+        Selector(json!({ "$not": self.0 }))
+    }
+}
+
+impl From<Value> for Selector {
+    fn from(selector: Value) -> Selector {
+        Selector(selector)
+    }
+}
+
+impl From<Selector> for Value {
+    fn from(selector: Selector) -> Value {
+        selector.0
+    }
+}
+
+/// Operators a selector can hold
+const OPERATORS: &[&str] = &[
+    "$and", "$or", "$not", "$nor", "$all", "$elemMatch", "$allMatch", "$keyMapMatch", "$eq", "$ne", "$lt", "$lte",
+    "$gt", "$gte", "$exists", "$type", "$in", "$nin", "$size", "$mod", "$regex", "$beginsWith", "$text",
+];
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
+
+/// Checks that a key of a selector is either a field, or a known operator,
+/// for `selector!` to reject unknown operators at compile time
+#[doc(hidden)]
+pub const fn is_selector_key(key: &str) -> bool {
+    if key.is_empty() || key.as_bytes()[0] != b'$' {
+        return true;
+    }
+
+    let mut i = 0;
+    while i < OPERATORS.len() {
+        if str_eq(key, OPERATORS[i]) {
+            return true;
+        }
+        i += 1;
+    }
+
+    false
+}
+
+/// Field a condition is being built on, see `Selector::field`
+#[derive(Debug, Clone)]
+pub struct Field {
+    name: Option<String>,
+}
+
+impl Field {
+    fn condition(self, operator: &str, value: Value) -> Selector {
+        let mut condition = Map::new();
+        condition.insert(s!(operator), value);
+
+        match self.name {
+            Some(name) => {
+                let mut map = Map::new();
+                map.insert(name, Value::Object(condition));
+                Selector(Value::Object(map))
+            }
+            None => Selector(Value::Object(condition)),
+        }
+    }
+
+    pub fn eq<V: Into<Value>>(self, value: V) -> Selector {
+        self.condition("$eq", value.into())
+    }
+
+    pub fn ne<V: Into<Value>>(self, value: V) -> Selector {
+        self.condition("$ne", value.into())
+    }
+
+    pub fn gt<V: Into<Value>>(self, value: V) -> Selector {
+        self.condition("$gt", value.into())
+    }
+
+    pub fn gte<V: Into<Value>>(self, value: V) -> Selector {
+        self.condition("$gte", value.into())
+    }
+
+    pub fn lt<V: Into<Value>>(self, value: V) -> Selector {
+        self.condition("$lt", value.into())
+    }
+
+    pub fn lte<V: Into<Value>>(self, value: V) -> Selector {
+        self.condition("$lte", value.into())
+    }
+
+    /// Matches when the field is set, or unset when `exists` is `false`
+    pub fn exists(self, exists: bool) -> Selector {
+        self.condition("$exists", exists.into())
+    }
+
+    /// Matches when the field is of the given JSON type: `null`, `boolean`,
+    /// `number`, `string`, `array` or `object`
+    pub fn of_type(self, json_type: &str) -> Selector {
+        self.condition("$type", json_type.into())
+    }
+
+    /// Matches when the field equals one of the values (`$in`)
+    pub fn in_<V: Into<Value>>(self, values: Vec<V>) -> Selector {
+        self.condition("$in", values.into_iter().map(Into::into).collect())
+    }
+
+    /// Matches when the field equals none of the values
+    pub fn nin<V: Into<Value>>(self, values: Vec<V>) -> Selector {
+        self.condition("$nin", values.into_iter().map(Into::into).collect())
+    }
+
+    /// Matches when the field is an array of the given length
+    pub fn size(self, size: u64) -> Selector {
+        self.condition("$size", size.into())
+    }
+
+    /// Matches when the field is a number whose remainder by `divisor` is
+    /// `remainder`
+    pub fn modulo(self, divisor: i64, remainder: i64) -> Selector {
+        self.condition("$mod", json!([divisor, remainder]))
+    }
+
+    /// Matches when the field is a string matching a regular expression
+    pub fn regex(self, regex: &str) -> Selector {
+        self.condition("$regex", regex.into())
+    }
+
+    /// Matches when the field is an array holding all the values
+    pub fn all<V: Into<Value>>(self, values: Vec<V>) -> Selector {
+        self.condition("$all", values.into_iter().map(Into::into).collect())
+    }
+
+    /// Matches when the field is an array with an element matching the
+    /// selector
+    pub fn elem_match(self, selector: Selector) -> Selector {
+        self.condition("$elemMatch", selector.0)
+    }
+
+    /// Matches when the field is an array whose elements all match the
+    /// selector
+    pub fn all_match(self, selector: Selector) -> Selector {
+        self.condition("$allMatch", selector.0)
+    }
+}
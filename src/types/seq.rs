@@ -0,0 +1,59 @@
+use std::fmt;
+
+/// Sequence of a database, identifying a point in its history. CouchDB 1.x
+/// sends integers, while 2.x and later send opaque strings which are only
+/// meant to be passed back to the server, e.g. as the `since` parameter of a
+/// changes feed.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Debug, Clone)]
+#[serde(untagged)]
+pub enum Seq {
+    /// CouchDB 1.x sequence
+    Number(u64),
+    /// CouchDB 2.x and later sequence, or a special value such as `now`
+    Opaque(String)
+}
+
+impl Seq {
+    /// Special `since` value skipping past changes
+    pub fn now() -> Seq {
+        Seq::Opaque(s!("now"))
+    }
+
+    /// Number of updates the sequence accounts for: the sequence itself on
+    /// CouchDB 1.x, its numeric prefix on later versions. Sequences of
+    /// different shards of a cluster are not comparable through it, and it
+    /// should only be used as an indication of progress.
+    pub fn number(&self) -> Option<u64> {
+        match *self {
+            Seq::Number(n) => Some(n),
+            Seq::Opaque(ref seq) => seq.split('-').next().and_then(|n| n.parse().ok())
+        }
+    }
+}
+
+impl fmt::Display for Seq {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Seq::Number(n) => write!(f, "{}", n),
+            Seq::Opaque(ref seq) => f.write_str(seq)
+        }
+    }
+}
+
+impl From<u64> for Seq {
+    fn from(n: u64) -> Seq {
+        Seq::Number(n)
+    }
+}
+
+impl From<String> for Seq {
+    fn from(seq: String) -> Seq {
+        Seq::Opaque(seq)
+    }
+}
+
+impl<'a> From<&'a str> for Seq {
+    fn from(seq: &'a str) -> Seq {
+        Seq::Opaque(s!(seq))
+    }
+}
@@ -1,3 +1,45 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::Seq;
+
+/// Parameters of `/_all_dbs`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct AllDbsParams {
+    /// Only returns the databases whose name sorts after this one, included
+    pub start_key: Option<String>,
+    /// Only returns the databases whose name sorts before this one, included
+    pub end_key: Option<String>,
+    pub limit: Option<u64>,
+    pub skip: Option<u64>,
+    pub descending: Option<bool>
+}
+
+impl AllDbsParams {
+    pub fn to_args(&self) -> HashMap<String, String> {
+        let mut args = HashMap::new();
+
+        if let Some(ref start_key) = self.start_key {
+            args.insert(s!("start_key"), js!(start_key));
+        }
+        if let Some(ref end_key) = self.end_key {
+            args.insert(s!("end_key"), js!(end_key));
+        }
+        if let Some(limit) = self.limit {
+            args.insert(s!("limit"), limit.to_string());
+        }
+        if let Some(skip) = self.skip {
+            args.insert(s!("skip"), skip.to_string());
+        }
+        if let Some(descending) = self.descending {
+            args.insert(s!("descending"), descending.to_string());
+        }
+
+        args
+    }
+}
+
 /// Couch vendor abstraction
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
 pub struct CouchVendor {
@@ -22,3 +64,77 @@ pub struct CouchResponse {
     pub error: Option<String>,
     pub reason: Option<String>
 }
+
+/// Couch session abstraction
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct CouchSession {
+    pub ok: Option<bool>,
+    pub name: Option<String>,
+    pub roles: Option<Vec<String>>,
+    pub error: Option<String>,
+    pub reason: Option<String>
+}
+
+/// Server-generated UUIDs, as returned by `/_uuids`
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct CouchUuids {
+    pub uuids: Vec<String>
+}
+
+/// Task running on the server, as listed by `/_active_tasks`. Fields
+/// specific to a kind of task are only set for that kind.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ActiveTask {
+    /// Kind of task: `indexer`, `database_compaction`, `view_compaction`,
+    /// `replication`...
+    #[serde(rename = "type")]
+    pub task_type: String,
+    pub node: Option<String>,
+    pub pid: Option<String>,
+    pub started_on: Option<u64>,
+    pub updated_on: Option<u64>,
+    /// Completion percentage
+    pub progress: Option<u64>,
+    pub database: Option<String>,
+    pub design_document: Option<String>,
+    pub phase: Option<String>,
+    pub changes_done: Option<u64>,
+    pub total_changes: Option<u64>,
+    pub replication_id: Option<String>,
+    pub doc_id: Option<String>,
+    pub source: Option<String>,
+    pub target: Option<String>,
+    pub continuous: Option<bool>,
+    pub docs_read: Option<u64>,
+    pub docs_written: Option<u64>,
+    pub doc_write_failures: Option<u64>,
+    pub changes_pending: Option<u64>,
+    pub checkpointed_source_seq: Option<Seq>,
+    pub source_seq: Option<Seq>
+}
+
+/// Health of a node, as returned by `/_up`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct UpStatus {
+    /// `ok`, or `maintenance_mode` / `nolb` when the node should not receive
+    /// traffic
+    pub status: String,
+    /// Seed nodes the node is still syncing with, if any
+    pub seeds: Option<Value>
+}
+
+impl UpStatus {
+    /// Checks if the node is ready to serve requests
+    pub fn is_up(&self) -> bool {
+        self.status == "ok"
+    }
+}
+
+/// Cluster topology, as returned by `/_membership`
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct Membership {
+    /// Nodes the server knows about
+    pub all_nodes: Vec<String>,
+    /// Nodes taking part in the cluster
+    pub cluster_nodes: Vec<String>
+}
@@ -0,0 +1,56 @@
+use serde_json::{Map, Value};
+
+/// Prefix of the IDs of the documents of the `_users` database
+pub const USER_ID_PREFIX: &str = "org.couchdb.user:";
+
+/// User, as stored in the `_users` database. The server replaces `password`
+/// with its derived key on write, the `derived_key`, `salt`, `iterations` and
+/// `password_scheme` fields having to be sent back on updates for the
+/// password to be kept. Fields not modeled here, such as `pbkdf2_prf` or
+/// custom profile fields, are kept in `extra`.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub struct UserDocument {
+    #[serde(rename = "_id")]
+    pub id: String,
+    #[serde(rename = "_rev", skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+    pub name: String,
+    pub roles: Vec<String>,
+    #[serde(rename = "type")]
+    pub doc_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password_scheme: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iterations: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub derived_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>
+}
+
+impl UserDocument {
+    pub fn new(name: &str, password: &str, roles: Vec<String>) -> UserDocument {
+        UserDocument {
+            id: user_id(name),
+            rev: None,
+            name: s!(name),
+            roles,
+            doc_type: s!("user"),
+            password: Some(s!(password)),
+            password_scheme: None,
+            iterations: None,
+            derived_key: None,
+            salt: None,
+            extra: Map::new()
+        }
+    }
+}
+
+/// Builds the ID of the document of the `name` user
+pub fn user_id(name: &str) -> String {
+    format!("{}{}", USER_ID_PREFIX, name)
+}
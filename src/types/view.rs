@@ -0,0 +1,317 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::{DocumentId, Seq};
+
+/// Row of a view, with its emitted key and value. `id` is the ID of the
+/// document that emitted the row, left out of reduced rows, and `doc` is
+/// that document, when queried with `include_docs`.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ViewRow<K, V> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<DocumentId>,
+    pub key: K,
+    pub value: V,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<Value>
+}
+
+/// Result of a view query. `total_rows` and `offset` are left out of
+/// reduced results.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ViewResponse<K, V> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_rows: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    pub rows: Vec<ViewRow<K, V>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_seq: Option<Seq>
+}
+
+impl<K, V> ViewResponse<K, V> {
+    /// Value of a reduced result that is not grouped, if any rows were
+    /// reduced
+    pub fn value(&self) -> Option<&V> {
+        self.rows.first().map(|row| &row.value)
+    }
+}
+
+/// Output of the built-in `_sum` reduce function: a number, or the sums of
+/// each item when arrays of numbers were emitted
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(untagged)]
+pub enum Sum {
+    Number(f64),
+    Array(Vec<f64>)
+}
+
+impl Sum {
+    /// The sum, unless arrays were summed
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Sum::Number(sum) => Some(sum),
+            Sum::Array(_) => None
+        }
+    }
+}
+
+/// Output of the built-in `_count` reduce function
+pub type Count = u64;
+
+/// Output of the built-in `_stats` reduce function over emitted numbers.
+/// Over arrays of numbers, it outputs a `Vec<Stats>`, one per item.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Stats {
+    pub sum: f64,
+    pub count: u64,
+    pub min: f64,
+    pub max: f64,
+    /// Sum of the squares of the values
+    pub sumsqr: f64
+}
+
+impl Stats {
+    pub fn mean(&self) -> Option<f64> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.sum / self.count as f64)
+        }
+    }
+}
+
+/// Results of `POST /{db}/_design/{ddoc}/_view/{view}/queries`, in the order
+/// of the queries
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ViewQueriesResponse<K, V> {
+    pub results: Vec<ViewResponse<K, V>>
+}
+
+/// Whether a view is brought up to date before being read
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ViewUpdate {
+    /// Updates the view first, the default
+    True,
+    /// Reads the view as it is
+    False,
+    /// Reads the view as it is, then updates it
+    Lazy
+}
+
+impl ViewUpdate {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ViewUpdate::True => "true",
+            ViewUpdate::False => "false",
+            ViewUpdate::Lazy => "lazy"
+        }
+    }
+}
+
+/// Parameters of a view query, built fluently. Keys are given as any
+/// serializable value, and sent JSON-encoded:
+///
+/// ```
+/// # extern crate sofa;
+/// # use sofa::types::{ViewQuery, ViewUpdate};
+/// # fn main() {
+/// let query = ViewQuery::new()
+///     .start_key(vec!["red", "a"])
+///     .end_key(vec!["red", "z"])
+///     .include_docs(true)
+///     .update(ViewUpdate::Lazy)
+///     .limit(10);
+/// # }
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct ViewQuery {
+    /// Only returns the rows emitted with this key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<Value>,
+    /// Only returns the rows emitted with these keys, in their order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keys: Option<Vec<Value>>,
+    /// Only returns the rows whose key sorts after this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_key: Option<Value>,
+    /// Along with `start_key`, only returns the rows emitted by documents
+    /// whose ID sorts after this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_key_doc_id: Option<String>,
+    /// Only returns the rows whose key sorts before this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_key: Option<Value>,
+    /// Along with `end_key`, only returns the rows emitted by documents
+    /// whose ID sorts before this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_key_doc_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub descending: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip: Option<u64>,
+    /// Groups the reduced rows by key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<bool>,
+    /// Groups the reduced rows by the first items of their array keys
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_level: Option<u64>,
+    /// Whether the reduce function is run, when the view has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reduce: Option<bool>,
+    /// Includes the documents that emitted the rows
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_docs: Option<bool>,
+    /// Includes the rows whose key is `end_key`, the default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inclusive_end: Option<bool>,
+    /// Reads from a stable set of shard replicas
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update: Option<ViewUpdate>
+}
+
+impl ViewQuery {
+    pub fn new() -> ViewQuery {
+        ViewQuery::default()
+    }
+
+    pub fn key<K: Serialize>(mut self, key: K) -> ViewQuery {
+        self.key = Some(json!(key));
+        self
+    }
+
+    pub fn keys<K: Serialize>(mut self, keys: Vec<K>) -> ViewQuery {
+        self.keys = Some(keys.into_iter().map(|key| json!(key)).collect());
+        self
+    }
+
+    pub fn start_key<K: Serialize>(mut self, start_key: K) -> ViewQuery {
+        self.start_key = Some(json!(start_key));
+        self
+    }
+
+    pub fn start_key_doc_id(mut self, id: &str) -> ViewQuery {
+        self.start_key_doc_id = Some(s!(id));
+        self
+    }
+
+    pub fn end_key<K: Serialize>(mut self, end_key: K) -> ViewQuery {
+        self.end_key = Some(json!(end_key));
+        self
+    }
+
+    pub fn end_key_doc_id(mut self, id: &str) -> ViewQuery {
+        self.end_key_doc_id = Some(s!(id));
+        self
+    }
+
+    pub fn descending(mut self, descending: bool) -> ViewQuery {
+        self.descending = Some(descending);
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> ViewQuery {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn skip(mut self, skip: u64) -> ViewQuery {
+        self.skip = Some(skip);
+        self
+    }
+
+    pub fn group(mut self, group: bool) -> ViewQuery {
+        self.group = Some(group);
+        self
+    }
+
+    pub fn group_level(mut self, group_level: u64) -> ViewQuery {
+        self.group_level = Some(group_level);
+        self
+    }
+
+    pub fn reduce(mut self, reduce: bool) -> ViewQuery {
+        self.reduce = Some(reduce);
+        self
+    }
+
+    pub fn include_docs(mut self, include_docs: bool) -> ViewQuery {
+        self.include_docs = Some(include_docs);
+        self
+    }
+
+    pub fn inclusive_end(mut self, inclusive_end: bool) -> ViewQuery {
+        self.inclusive_end = Some(inclusive_end);
+        self
+    }
+
+    pub fn stable(mut self, stable: bool) -> ViewQuery {
+        self.stable = Some(stable);
+        self
+    }
+
+    pub fn update(mut self, update: ViewUpdate) -> ViewQuery {
+        self.update = Some(update);
+        self
+    }
+
+    /// Query string arguments of the parameters, keys being JSON-encoded.
+    /// `keys` is left out, as it is sent in the body of a `POST`.
+    pub fn to_args(&self) -> HashMap<String, String> {
+        let mut args = HashMap::new();
+
+        if let Some(ref key) = self.key {
+            args.insert(s!("key"), js!(key));
+        }
+        if let Some(ref start_key) = self.start_key {
+            args.insert(s!("start_key"), js!(start_key));
+        }
+        if let Some(ref start_key_doc_id) = self.start_key_doc_id {
+            args.insert(s!("start_key_doc_id"), start_key_doc_id.clone());
+        }
+        if let Some(ref end_key) = self.end_key {
+            args.insert(s!("end_key"), js!(end_key));
+        }
+        if let Some(ref end_key_doc_id) = self.end_key_doc_id {
+            args.insert(s!("end_key_doc_id"), end_key_doc_id.clone());
+        }
+        if let Some(descending) = self.descending {
+            args.insert(s!("descending"), descending.to_string());
+        }
+        if let Some(limit) = self.limit {
+            args.insert(s!("limit"), limit.to_string());
+        }
+        if let Some(skip) = self.skip {
+            args.insert(s!("skip"), skip.to_string());
+        }
+        if let Some(group) = self.group {
+            args.insert(s!("group"), group.to_string());
+        }
+        if let Some(group_level) = self.group_level {
+            args.insert(s!("group_level"), group_level.to_string());
+        }
+        if let Some(reduce) = self.reduce {
+            args.insert(s!("reduce"), reduce.to_string());
+        }
+        if let Some(include_docs) = self.include_docs {
+            args.insert(s!("include_docs"), include_docs.to_string());
+        }
+        if let Some(inclusive_end) = self.inclusive_end {
+            args.insert(s!("inclusive_end"), inclusive_end.to_string());
+        }
+        if let Some(stable) = self.stable {
+            args.insert(s!("stable"), stable.to_string());
+        }
+        if let Some(update) = self.update {
+            args.insert(s!("update"), s!(update.as_str()));
+        }
+
+        args
+    }
+}
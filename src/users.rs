@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use reqwest::StatusCode;
+
+use client::*;
+use error::SofaError;
+use request::*;
+use types::*;
+
+/// Name of the users database
+pub const USERS_DB: &str = "_users";
+
+/// Users manages the accounts of the `_users` database. Writes are
+/// optimistic: updating a user with a stale revision returns the conflict as
+/// an `Err`, to be resolved by fetching the user again.
+#[derive(Debug, Clone)]
+pub struct Users {
+    _client: Client,
+}
+
+impl Users {
+    pub fn new(client: Client) -> Users {
+        Users { _client: client }
+    }
+
+    fn create_user_path(&self, name: &str) -> String {
+        format!("{}/{}", USERS_DB, encode_path_segment(&user_id(name)))
+    }
+
+    /// Creates a user, failing with a conflict if it already exists
+    pub fn create(&self, name: &str, password: &str, roles: Vec<String>) -> Result<UserDocument, SofaError> {
+        self.save(UserDocument::new(name, password, roles))
+    }
+
+    /// Fetches a user
+    pub fn get(&self, name: &str) -> Result<UserDocument, SofaError> {
+        let response = self._client.get(self.create_user_path(name), None)?.send()?;
+
+        read_json(response)
+    }
+
+    /// Writes a user, created or fetched beforehand. The returned user holds
+    /// the new revision, the password being left as set on `user`.
+    pub fn save(&self, mut user: UserDocument) -> Result<UserDocument, SofaError> {
+        let response = self._client.put(self.create_user_path(&user.name), js!(&user))?.send()?;
+        if response.status() == StatusCode::Conflict {
+            return Err(conflict_error(&user.id, response));
+        }
+
+        let data: DocumentCreatedResult = read_json(response)?;
+
+        match data.ok {
+            Some(true) => {
+                user.rev = data.rev;
+                Ok(user)
+            }
+            _ => {
+                let err = data.reason.or(data.error).unwrap_or(s!("unspecified error"));
+                Err(SofaError::Custom(err))
+            }
+        }
+    }
+
+    /// Changes the password of a user
+    pub fn change_password(&self, name: &str, password: &str) -> Result<UserDocument, SofaError> {
+        let mut user = self.get(name)?;
+        user.password = Some(s!(password));
+
+        self.save(user)
+    }
+
+    /// Deletes a user at its current revision
+    pub fn delete(&self, name: &str) -> Result<bool, SofaError> {
+        let user = self.get(name)?;
+
+        let mut args = HashMap::new();
+        args.insert(s!("rev"), user.rev.unwrap_or_default());
+
+        let response = self._client.delete(self.create_user_path(name), Some(args))?.send()?;
+        let s: CouchResponse = read_json(response)?;
+
+        Ok(s.ok.unwrap_or(false))
+    }
+}